@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use serenity::model::id::GuildId;
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    io,
+};
+
+use crate::json_store;
+
+// Command aliases set up by each guild's admins, mapping a name to the command it stands for
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AliasRegistry {
+    #[serde(skip)]
+    path: String,
+    aliases: HashMap<u64, BTreeMap<String, String>>,
+}
+
+impl AliasRegistry {
+    pub async fn load(path: &str) -> io::Result<AliasRegistry> {
+        let mut registry: AliasRegistry = json_store::load(path).await?;
+        registry.path = path.to_string();
+
+        Ok(registry)
+    }
+
+    pub fn get(&self, guild: GuildId, name: &str) -> Option<&String> {
+        self.list(guild)?.get(&name.to_lowercase())
+    }
+
+    pub fn list(&self, guild: GuildId) -> Option<&BTreeMap<String, String>> {
+        self.aliases.get(&guild.0)
+    }
+
+    pub async fn save(&mut self, guild: GuildId, name: &str, command: &str) -> io::Result<()> {
+        self.aliases
+            .entry(guild.0)
+            .or_default()
+            .insert(name.to_lowercase(), command.to_string());
+
+        self.persist().await
+    }
+
+    // Returns whether there was an alias to remove
+    pub async fn remove(&mut self, guild: GuildId, name: &str) -> io::Result<bool> {
+        let removed = self.aliases
+            .get_mut(&guild.0)
+            .and_then(|guild_aliases| guild_aliases.remove(&name.to_lowercase()))
+            .is_some();
+
+        if removed { self.persist().await?; }
+
+        Ok(removed)
+    }
+
+    // Rewrites a message like `!wodx 5` into `!wod 10again 5` if the first word is an alias.
+    // Only one alias is expanded, so aliases can't loop into each other. Built-in commands come first, so an alias
+    // saved before a command with the same name existed can't hide it.
+    pub fn expand(&self, guild: GuildId, prefix: &str, content: &str, commands: &[String]) -> Option<String> {
+        let command = content.strip_prefix(prefix)?.trim_start();
+        let (name, rest) = match command.split_once(char::is_whitespace) {
+            Some((name, rest)) => (name, rest),
+            None => (command, ""),
+        };
+
+        if commands.iter().any(|command| command.eq_ignore_ascii_case(name)) { return None; }
+        let expansion = self.get(guild, name)?;
+
+        Some(format!("{}{} {}", prefix, expansion, rest).trim_end().to_string())
+    }
+
+    async fn persist(&self) -> io::Result<()> {
+        json_store::save(&self.path, self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(aliases: &[(&str, &str)]) -> AliasRegistry {
+        let guild_aliases = aliases.iter().map(|(name, command)| (name.to_string(), command.to_string())).collect();
+        let mut registry = AliasRegistry::default();
+        registry.aliases.insert(1, guild_aliases);
+
+        registry
+    }
+
+    #[test]
+    fn expands_only_the_first_word() {
+        let registry = registry(&[("wodx", "wod 10again")]);
+
+        assert_eq!(registry.expand(GuildId(1), "!", "!wodx 5", &[]), Some("!wod 10again 5".to_string()));
+        assert_eq!(registry.expand(GuildId(1), "!", "!WODX", &[]), Some("!wod 10again".to_string()));
+        assert_eq!(registry.expand(GuildId(1), "!", "!roll wodx", &[]), None);
+        assert_eq!(registry.expand(GuildId(2), "!", "!wodx 5", &[]), None);
+    }
+
+    #[test]
+    fn built_in_commands_win_over_aliases() {
+        let registry = registry(&[("roll", "wod 5"), ("unalias", "roll 1d6")]);
+        let commands = vec!["roll".to_string(), "unalias".to_string()];
+
+        assert_eq!(registry.expand(GuildId(1), "!", "!roll 2d6", &commands), None);
+        assert_eq!(registry.expand(GuildId(1), "!", "!Unalias roll", &commands), None);
+    }
+}
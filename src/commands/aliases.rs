@@ -0,0 +1,154 @@
+use serenity::{
+    framework::{
+        standard::{
+            Args,
+            CommandResult,
+            macros::{
+                command,
+            },
+        },
+    },
+    model::channel::Message,
+    prelude::*,
+};
+
+use crate::messaging::MAX_MESSAGE_LENGTH;
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_GUILD")]
+#[description = "Adds a command alias for this server.\n\n
+Give me the new name and then the command it stands for, like `!alias wodx wod 10again`. After that `!wodx 5` works the same as `!wod 10again 5`. Aliasing a name that's already an alias replaces it. Aliases can't use the name of one of my commands."]
+async fn alias(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let name = match args.single::<String>() {
+        Ok(name) => name.to_lowercase(),
+        Err(_) => {
+            let name_error = "☢ What should the alias be called? ☢\nGive me a name and a command, like `!alias wodx wod 10again`.".to_string();
+            msg.channel_id.say(&ctx.http, name_error).await?;
+            return Ok(());
+        }
+    };
+    let command = args.rest().trim().to_string();
+
+    // Built-in commands always run before aliases, so an alias with one of their names would never be used. This
+    // also means !alias and !unalias can never be hidden behind an alias.
+    let is_command = {
+        let names_data = ctx.data.read().await;
+        let names = names_data.get::<crate::CommandNamesKey>().expect("Failed to retrieve command names!");
+        names.iter().any(|command_name| command_name.eq_ignore_ascii_case(&name))
+    };
+    if is_command {
+        let name_error = format!("☢ I can't use that name! ☢\n`{}` is already one of my commands.", name);
+        msg.channel_id.say(&ctx.http, name_error).await?;
+        return Ok(());
+    }
+
+    if command.is_empty() {
+        let command_error = format!("☢ What should `{}` do? ☢\nGive me a command after the name, like `!alias wodx wod 10again`.", name);
+        msg.channel_id.say(&ctx.http, command_error).await?;
+        return Ok(());
+    }
+
+    let max_aliases = {
+        let config_data = ctx.data.read().await;
+        let cfg = config_data.get::<crate::ConfigKey>().expect("Failed to retrieve config!");
+        cfg.aliases.max_per_guild
+    };
+
+    let alias_data = ctx.data.read().await;
+    let mut registry = alias_data
+        .get::<crate::AliasesKey>()
+        .expect("Failed to retrieve alias registry!")
+        .lock().await;
+
+    let saved = registry.list(guild_id).map(|aliases| aliases.len()).unwrap_or(0);
+    let replacing = registry.get(guild_id, &name).is_some();
+    if !replacing && saved >= max_aliases {
+        let limit_error = format!("☢ This server already has {} aliases! ☢\nRemove one with `!unalias <name>` first.", saved);
+        msg.channel_id.say(&ctx.http, limit_error).await?;
+        return Ok(());
+    }
+
+    if let Err(why) = registry.save(guild_id, &name, &command).await {
+        let save_error = format!("☢ Something went wrong! ☢\n Error saving alias: {}", why);
+        msg.channel_id.say(&ctx.http, save_error).await?;
+        return Ok(());
+    }
+
+    let alias_confirm = format!("Okay! `{}` now means `{}`! ❤", name, command);
+    msg.channel_id.say(&ctx.http, alias_confirm).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_GUILD")]
+#[description = "Removes one of this server's command aliases.\n\n
+Give me the name of the alias, like `!unalias wodx`."]
+async fn unalias(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let name = args.rest().trim().to_lowercase();
+
+    let alias_data = ctx.data.read().await;
+    let mut registry = alias_data
+        .get::<crate::AliasesKey>()
+        .expect("Failed to retrieve alias registry!")
+        .lock().await;
+
+    let unalias = match registry.remove(guild_id, &name).await {
+        Ok(true) => format!("Okay, `{}` isn't an alias anymore!", name),
+        Ok(false) => format!("This server doesn't have an alias called `{}`!", name),
+        Err(why) => format!("☢ Something went wrong! ☢\n Error deleting alias: {}", why),
+    };
+    msg.channel_id.say(&ctx.http, unalias).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[description = "Lists this server's command aliases."]
+async fn aliases(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let alias_data = ctx.data.read().await;
+    let registry = alias_data
+        .get::<crate::AliasesKey>()
+        .expect("Failed to retrieve alias registry!")
+        .lock().await;
+
+    let listing = match registry.list(guild_id) {
+        Some(aliases) if !aliases.is_empty() => {
+            let lines = aliases
+                .iter()
+                .map(|(name, command)| format!("`{}` → `{}`", name, command))
+                .collect::<Vec<String>>()
+                .join("\n");
+            let names = aliases.keys().map(|name| format!("`{}`", name)).collect::<Vec<String>>().join(", ");
+
+            // Long commands won't all fit in one message, so fall back to just the names
+            let full = format!("Here are this server's aliases:\n{}", lines);
+            if full.chars().count() > MAX_MESSAGE_LENGTH {
+                format!("Here are this server's aliases: {}", names)
+            } else {
+                full
+            }
+        },
+        _ => "This server doesn't have any aliases yet!".to_string(),
+    };
+    msg.channel_id.say(&ctx.http, listing).await?;
+
+    Ok(())
+}
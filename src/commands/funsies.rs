@@ -1,8 +1,12 @@
 use serenity::{
     framework::{
         standard::{
+            Args,
+            CommandOptions,
             CommandResult,
+            Reason,
             macros::{
+                check,
                 command,
             },
         },
@@ -11,10 +15,36 @@ use serenity::{
     prelude::*,
 };
 
+use crate::messaging::cleanup::schedule_cleanup;
+
+// Servers can turn funsies off or keep them to some channels with !funsiesconfig. Blocked commands are ignored
+// quietly so they don't clutter the channel.
+#[check]
+#[name = "funsies_allowed"]
+async fn funsies_allowed(ctx: &Context, msg: &Message, _: &mut Args, _: &CommandOptions) -> Result<(), Reason> {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let settings_data = ctx.data.read().await;
+    let registry = settings_data
+        .get::<crate::SettingsKey>()
+        .expect("Failed to retrieve guild settings!")
+        .lock().await;
+
+    if registry.get(guild_id).funsies.allowed_in(msg.channel_id) {
+        Ok(())
+    } else {
+        Err(Reason::Log(format!("Funsies are not allowed in channel {}", msg.channel_id)))
+    }
+}
+
 #[command]
 async fn squid(ctx: &Context, msg: &Message) -> CommandResult {
     let squid = format!("{} ＜コ:彡", msg.author);
-    msg.channel_id.say(&ctx.http, squid).await?;
+    let reply = msg.channel_id.say(&ctx.http, squid).await?;
+    schedule_cleanup(ctx, reply).await;
 
     Ok(())
 }
@@ -30,7 +60,8 @@ async fn shadow(ctx: &Context, msg: &Message) -> CommandResult {
 \t\t\t\tAnd never, ever deal with a dragon!
 (ﾉ≧∀≦)ﾉ"
     );
-    msg.channel_id.say(&ctx.http, rules).await?;
+    let reply = msg.channel_id.say(&ctx.http, rules).await?;
+    schedule_cleanup(ctx, reply).await;
 
     Ok(())
 }
@@ -38,7 +69,8 @@ async fn shadow(ctx: &Context, msg: &Message) -> CommandResult {
 #[command]
 async fn unyu(ctx: &Context, msg: &Message) -> CommandResult {
     let unyu = format!("{} うにゅうー！", msg.author);
-    msg.channel_id.say(&ctx.http, unyu).await?;
+    let reply = msg.channel_id.say(&ctx.http, unyu).await?;
+    schedule_cleanup(ctx, reply).await;
 
     Ok(())
 }
@@ -46,7 +78,8 @@ async fn unyu(ctx: &Context, msg: &Message) -> CommandResult {
 #[command]
 async fn atom(ctx: &Context, msg: &Message) -> CommandResult {
     let atom = String::from("(●o≧д≦) Up and atom! ☢ 😤 ☢");
-    msg.channel_id.say(&ctx.http, atom).await?;
+    let reply = msg.channel_id.say(&ctx.http, atom).await?;
+    schedule_cleanup(ctx, reply).await;
 
     Ok(())
 }
@@ -55,7 +88,8 @@ async fn atom(ctx: &Context, msg: &Message) -> CommandResult {
 #[aliases("sway", "shimarin", "shima")]
 async fn yuru(ctx: &Context, msg: &Message) -> CommandResult {
     let sway = String::from("https://tenor.com/view/yuru-camp-shima-rin-gif-19870064");
-    msg.channel_id.say(&ctx.http, sway).await?;
+    let reply = msg.channel_id.say(&ctx.http, sway).await?;
+    schedule_cleanup(ctx, reply).await;
 
     Ok(())
 }
\ No newline at end of file
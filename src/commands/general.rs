@@ -16,6 +16,11 @@ use std::{
     path::Path,
 };
 
+use crate::{
+    messaging::MAX_MESSAGE_LENGTH,
+    usage::CommandUsage,
+};
+
 #[command]
 #[description = "Ping-pong command to check if I'm online."]
 async fn ping(ctx: &Context, msg: &Message) -> CommandResult {
@@ -74,4 +79,40 @@ async fn hello(ctx: &Context, msg: &Message) -> CommandResult {
     }).await?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[command]
+#[description = "Shows how often each of my commands has been used here.\n\n
+In a server, this counts everyone's commands in the server. I only count which commands were used and whether they failed, never what was said. Server admins can turn this off with !usagetracking."]
+async fn usage(ctx: &Context, msg: &Message) -> CommandResult {
+    let usage_data = ctx.data.read().await;
+    let registry = usage_data
+        .get::<crate::UsageKey>()
+        .expect("Failed to retrieve usage registry!")
+        .lock().await;
+
+    let mut counts = registry
+        .get(msg.guild_id)
+        .map(|usage| usage.iter().map(|(name, usage)| (name.clone(), *usage)).collect::<Vec<(String, CommandUsage)>>())
+        .unwrap_or_default();
+    counts.sort_by_key(|(_, usage)| std::cmp::Reverse(usage.uses));
+
+    let usage = if counts.is_empty() {
+        "I haven't counted any commands here yet!".to_string()
+    } else {
+        let mut usage = "Here's how often my commands get used here:".to_string();
+        // The least used commands are left off if they don't all fit in one message
+        for (name, count) in &counts {
+            let mut line = format!("\n`{}`: {} use{}", name, count.uses, if count.uses == 1 { "" } else { "s" });
+            if count.errors > 0 {
+                line.push_str(&format!(", {} error{}", count.errors, if count.errors == 1 { "" } else { "s" }));
+            }
+            if usage.chars().count() + line.chars().count() > MAX_MESSAGE_LENGTH { break; }
+            usage.push_str(&line);
+        }
+        usage
+    };
+    msg.channel_id.say(&ctx.http, usage).await?;
+
+    Ok(())
+}
@@ -35,10 +35,8 @@ pub type LogsMap = HashMap<ChannelId, Logger>;
 I'll keep logging until someone tells me to stop with !unlog.\n
 !log without an argument will log the channel the command was used in. To log a different channel, pass a mention to that channel as a command: `!log #general`."]
 async fn log(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
-    let target;
-
-    match resolve_channel_mention(msg, args) {
-        Ok(id) => target = id,
+    let target = match resolve_channel_mention(msg, args) {
+        Ok(id) => id,
         Err(why) => {
             let chan_error = format!("☢ That's not a channel I recognize! ☢\n Error parsing channel id: {}", why);
             msg.channel_id.say(&ctx.http, chan_error).await?;
@@ -46,9 +44,8 @@ async fn log(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
         }
     };
 
-    let allowed;
-    match check_logging_permission(target, msg.channel_id, ctx).await {
-        Ok(perm) => allowed = perm,
+    let allowed = match check_logging_permission(target, msg.channel_id, ctx).await {
+        Ok(perm) => perm,
         Err(why) => {
             let check_error = format!("☢ I don't know if I'm allowed to do that! ☢\n Error checking logging permission: {}", why);
             msg.channel_id.say(&ctx.http, check_error).await?;
@@ -62,21 +59,20 @@ async fn log(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
         return Ok(());
     }
 
-    let filename;
-    match construct_log_filename(target, ctx).await {
-        Ok(name) => filename = name,
+    let filename = match construct_log_filename(target, ctx).await {
+        Ok(name) => name,
         Err(why) => {
             let name_error = format!("☢ Something went wrong! ☢\n Error constructing log filename: {}", why);
             msg.channel_id.say(&ctx.http, name_error).await?;
             return Ok(());
         }
-    }
+    };
 
     let log;
     {
         let config_data = ctx.data.read().await;
         let cfg = config_data.get::<crate::ConfigKey>().expect("Failed to retrieve config!");
-        match Logger::new(&cfg.log_folder_path, &filename) {
+        match Logger::new(&cfg.logging.folder_path, &filename) {
             Ok(logger) => log = logger,
             Err(why) => {
                 let log_error = format!("☢ Something went wrong! ☢\n Error creating log file: {}", why);
@@ -106,10 +102,8 @@ async fn log(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 Once I stop logging, I'll post the log file in the channel the command was used in.\n
 !unlog without an argument will unlog the channel the command was used in. To unlog a different channel, pass a mention to that channel as a command: `!unlog #general`."]
 async fn unlog(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
-    let target;
-
-    match resolve_channel_mention(msg, args) {
-        Ok(id) => target = id,
+    let target = match resolve_channel_mention(msg, args) {
+        Ok(id) => id,
         Err(why) => {
             let chan_error = format!("☢ That's not a channel I recognize! ☢\n Error parsing channel id: {}", why);
             msg.channel_id.say(&ctx.http, chan_error).await?;
@@ -117,9 +111,8 @@ async fn unlog(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
         }
     };
 
-    let allowed;
-    match check_logging_permission(target, msg.channel_id, ctx).await {
-        Ok(perm) => allowed = perm,
+    let allowed = match check_logging_permission(target, msg.channel_id, ctx).await {
+        Ok(perm) => perm,
         Err(why) => {
             let check_error = format!("☢ I don't know if I'm allowed to do that! ☢\n Error checking logging permission: {}", why);
             msg.channel_id.say(&ctx.http, check_error).await?;
@@ -167,10 +160,8 @@ async fn unlog(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 #[description = "Check if a channel is being logged.\n\n
 !logging without an argument will check the channel the command was used in. To check a different channel, pass a mention to that channel as a command: `!logging #general`."]
 async fn logging(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
-    let target;
-
-    match resolve_channel_mention(msg, args) {
-        Ok(id) => target = id,
+    let target = match resolve_channel_mention(msg, args) {
+        Ok(id) => id,
         Err(why) => {
             let chan_error = format!("☢ That's not a channel I recognize! ☢\n Error parsing channel id: {}", why);
             msg.channel_id.say(&ctx.http, chan_error).await?;
@@ -178,9 +169,8 @@ async fn logging(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
         }
     };
 
-    let allowed;
-    match check_logging_permission(target, msg.channel_id, ctx).await {
-        Ok(perm) => allowed = perm,
+    let allowed = match check_logging_permission(target, msg.channel_id, ctx).await {
+        Ok(perm) => perm,
         Err(why) => {
             let check_error = format!("☢ I don't know if I'm allowed to do that! ☢\n Error checking logging permission: {}", why);
             msg.channel_id.say(&ctx.http, check_error).await?;
@@ -200,12 +190,11 @@ async fn logging(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
                     .expect("Failed to retrieve logs map!")
                     .lock().await;
 
-    let logging;
-    if log_map.contains_key(&target) {
-        logging = format!("{} I'm logging <#{}> right now!", msg.author, target);
+    let logging = if log_map.contains_key(&target) {
+        format!("{} I'm logging <#{}> right now!", msg.author, target)
     } else {
-        logging = format!("{} I'm not logging <#{}> yet!", msg.author, target);
-    }
+        format!("{} I'm not logging <#{}> yet!", msg.author, target)
+    };
     msg.channel_id.say(&ctx.http, logging).await?;
     
     Ok(())
@@ -247,13 +236,11 @@ async fn construct_log_filename(id: ChannelId, ctx: &Context) -> Result<String,
 }
 
 fn resolve_channel_mention(msg: &Message, mut args: Args) -> Result<ChannelId, ArgError<serenity::model::misc::ChannelIdParseError>> {
-    let target;
-
-    if args.len() == 0 {
-        target = msg.channel_id;
+    let target = if args.is_empty() {
+        msg.channel_id
     } else {
-        target = args.single::<ChannelId>()?;
-        };
+        args.single::<ChannelId>()?
+    };
 
     Ok(target)
 }
\ No newline at end of file
@@ -0,0 +1,146 @@
+use serenity::{
+    framework::{
+        standard::{
+            Args,
+            CommandResult,
+            macros::{
+                command,
+            },
+        },
+    },
+    model::channel::Message,
+    prelude::*,
+};
+
+use crate::{
+    dice::roll::RepeatedRoll,
+    messaging::MAX_MESSAGE_LENGTH,
+};
+
+#[command]
+#[aliases("savemacro")]
+#[description = "Saves a roll under a name so you can roll it again later.\n\n
+Give me the name and then the roll, like `!save attack 1d20+7`, and roll it with `!roll attack`. Saving under a name you already used replaces the old roll."]
+async fn save(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let name = match args.single::<String>() {
+        Ok(name) => name.to_lowercase(),
+        Err(_) => {
+            let name_error = "☢ What should I call it? ☢\nGive me a name and a roll, like `!save attack 1d20+7`.".to_string();
+            msg.channel_id.say(&ctx.http, name_error).await?;
+            return Ok(());
+        }
+    };
+    let expression = args.rest().trim().to_string();
+
+    if let Err(why) = check_macro_name(&name) {
+        let name_error = format!("☢ I can't use that name! ☢\n{}", why);
+        msg.channel_id.say(&ctx.http, name_error).await?;
+        return Ok(());
+    }
+
+    if let Err(why) = expression.parse::<RepeatedRoll>() {
+        let parse_error = format!("☢ I don't know how to roll that! ☢\n Error parsing dice: {}", why);
+        msg.channel_id.say(&ctx.http, parse_error).await?;
+        return Ok(());
+    }
+
+    let max_macros = {
+        let config_data = ctx.data.read().await;
+        let cfg = config_data.get::<crate::ConfigKey>().expect("Failed to retrieve config!");
+        cfg.macros.max_per_user
+    };
+
+    let macro_data = ctx.data.read().await;
+    let mut registry = macro_data
+        .get::<crate::MacrosKey>()
+        .expect("Failed to retrieve macro registry!")
+        .lock().await;
+
+    let saved = registry.list(msg.guild_id, msg.author.id).map(|macros| macros.len()).unwrap_or(0);
+    let replacing = registry.get(msg.guild_id, msg.author.id, &name).is_some();
+    if !replacing && saved >= max_macros {
+        let limit_error = format!("☢ You already have {} rolls saved! ☢\nDelete one with `!unsave <name>` first.", saved);
+        msg.channel_id.say(&ctx.http, limit_error).await?;
+        return Ok(());
+    }
+
+    if let Err(why) = registry.save(msg.guild_id, msg.author.id, &name, &expression).await {
+        let save_error = format!("☢ Something went wrong! ☢\n Error saving roll: {}", why);
+        msg.channel_id.say(&ctx.http, save_error).await?;
+        return Ok(());
+    }
+
+    let save_confirm = format!("{} Saved `{}` as `{}`! ❤", msg.author, expression, name);
+    msg.channel_id.say(&ctx.http, save_confirm).await?;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("delmacro", "forget")]
+#[description = "Deletes one of your saved rolls.\n\n
+Give me the name of the roll, like `!unsave attack`."]
+async fn unsave(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let name = args.rest().trim().to_lowercase();
+
+    let macro_data = ctx.data.read().await;
+    let mut registry = macro_data
+        .get::<crate::MacrosKey>()
+        .expect("Failed to retrieve macro registry!")
+        .lock().await;
+
+    let unsave = match registry.remove(msg.guild_id, msg.author.id, &name).await {
+        Ok(true) => format!("{} Okay, I forgot `{}`!", msg.author, name),
+        Ok(false) => format!("{} You don't have a roll called `{}`!", msg.author, name),
+        Err(why) => format!("☢ Something went wrong! ☢\n Error deleting roll: {}", why),
+    };
+    msg.channel_id.say(&ctx.http, unsave).await?;
+
+    Ok(())
+}
+
+#[command]
+#[description = "Lists your saved rolls."]
+async fn macros(ctx: &Context, msg: &Message) -> CommandResult {
+    let macro_data = ctx.data.read().await;
+    let registry = macro_data
+        .get::<crate::MacrosKey>()
+        .expect("Failed to retrieve macro registry!")
+        .lock().await;
+
+    let listing = match registry.list(msg.guild_id, msg.author.id) {
+        Some(macros) if !macros.is_empty() => {
+            let lines = macros
+                .iter()
+                .map(|(name, expression)| format!("`{}`: {}", name, expression))
+                .collect::<Vec<String>>()
+                .join("\n");
+            let names = macros.keys().map(|name| format!("`{}`", name)).collect::<Vec<String>>().join(", ");
+
+            // Long rolls won't all fit in one message, so fall back to just the names
+            let full = format!("{} Here are your saved rolls:\n{}", msg.author, lines);
+            if full.chars().count() > MAX_MESSAGE_LENGTH {
+                format!("{} Here are your saved rolls: {}", msg.author, names)
+            } else {
+                full
+            }
+        },
+        _ => format!("{} You don't have any saved rolls yet! Save one with `!save <name> <roll>`.", msg.author),
+    };
+    msg.channel_id.say(&ctx.http, listing).await?;
+
+    Ok(())
+}
+
+fn check_macro_name(name: &str) -> Result<(), String> {
+    if !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return Err("Names can only have letters, numbers, `-` and `_`.".to_string());
+    }
+
+    // `!roll` tries saved names first, so a name that is also a roll would hide that roll
+    if name.parse::<RepeatedRoll>().is_ok() {
+        return Err(format!("`{}` is already a roll by itself.", name));
+    }
+
+    Ok(())
+}
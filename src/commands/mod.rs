@@ -1,4 +1,8 @@
+pub mod aliases;
 pub mod general;
 pub mod logging;
+pub mod macros;
+pub mod mtg;
 pub mod rolling;
+pub mod settings;
 pub mod funsies;
\ No newline at end of file
@@ -0,0 +1,306 @@
+use serenity::{
+    framework::{
+        standard::{
+            Args,
+            CommandResult,
+            macros::{
+                command,
+            },
+        },
+    },
+    model::{
+        channel::Message,
+        id::UserId,
+    },
+    prelude::*,
+};
+
+use crate::{
+    dice::{
+        die::Die,
+        roll::MAX_REPEATS,
+        systems::mtg::{
+            COIN,
+            PLANAR_DIE,
+        },
+        RollError,
+    },
+    messaging::cleanup::track_reply,
+    mtg::{
+        DEFAULT_LIFE,
+        MtgTable,
+        MtgTables,
+    },
+};
+
+#[command]
+#[aliases("planechase", "pd")]
+#[description = "Rolls the Planechase planar die.\n\n
+One face planeswalks, one face triggers chaos and the other four do nothing."]
+async fn planar(ctx: &Context, msg: &Message) -> CommandResult {
+    let face = Die::roll_face(&PLANAR_DIE)?;
+
+    let planar = format!("{} You rolled the planar die: **{}**", msg.author, face);
+    let reply = msg.channel_id.say(&ctx.http, planar).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("coinflip", "flip")]
+#[description = "Flips a coin.\n\n
+Give me a number to flip several coins at once, like `!mtgflip 3`."]
+async fn mtgflip(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let count = if args.is_empty() { 1 } else {
+        match args.single::<u32>() {
+            Ok(count) if (1..=MAX_REPEATS).contains(&count) => count,
+            _ => {
+                let count_error = format!("☢ How many coins? ☢\nI can flip between 1 and {} coins at once.", MAX_REPEATS);
+                msg.channel_id.say(&ctx.http, count_error).await?;
+                return Ok(());
+            }
+        }
+    };
+
+    let flips = (0..count)
+        .map(|_| Die::roll_face(&COIN).map(|face| face.to_string()))
+        .collect::<Result<Vec<String>, RollError>>()?;
+    let flip = format!("{} You flipped **{}**", msg.author, flips.join(", "));
+    let reply = msg.channel_id.say(&ctx.http, flip).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[description = "Keeps track of who the monarch is in this channel's game.\n\n
+`!monarch` tells you who it is, `!monarch @someone` or `!monarch me` crowns someone new, and `!monarch none` clears it."]
+async fn monarch(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    track_holder(ctx, msg, args, "the monarch", |table| &mut table.monarch).await
+}
+
+#[command]
+#[only_in(guilds)]
+#[description = "Keeps track of who has the initiative in this channel's game.\n\n
+`!initiative` tells you who has it, `!initiative @someone` or `!initiative me` gives it to someone new, and `!initiative none` clears it."]
+async fn initiative(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    track_holder(ctx, msg, args, "the initiative", |table| &mut table.initiative).await
+}
+
+#[command]
+#[only_in(guilds)]
+#[description = "Keeps track of life totals for this channel's game.\n\n
+Start a game with `!life start @you @them @someone`, or put the starting life first for Commander: `!life start 40 @you @them`.
+`!life -3` or `!life +2` changes your own life, `!life @them -5` changes someone else's, and `!life cmd @them 6` records 6 commander damage from their commander to you (add another mention at the end for someone else). That also takes the life away.
+`!life` on its own shows everyone's totals, and `!life end` clears the game."]
+async fn life(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let reply = {
+        let tables_data = ctx.data.read().await;
+        let mut tables = tables_data
+            .get::<crate::MtgKey>()
+            .expect("Failed to retrieve MTG tables!")
+            .lock().await;
+
+        change_life(&mut tables, msg, &mut args).await
+    };
+    say_quietly(ctx, msg, reply).await?;
+
+    Ok(())
+}
+
+// Makes the change and saves it, returning what to reply with
+async fn change_life(tables: &mut MtgTables, msg: &Message, args: &mut Args) -> String {
+    let mut table = tables.get(msg.channel_id);
+    let action = args.current().unwrap_or_default().to_lowercase();
+    let change = match action.as_str() {
+        "" => Ok(()),
+        "start" | "new" => {
+            args.advance();
+            start_pod(&mut table, args)
+        },
+        "end" | "clear" => {
+            table.pod.clear();
+            Ok(())
+        },
+        "cmd" | "commander" => {
+            args.advance();
+            commander_damage(&mut table, args, msg.author.id)
+        },
+        _ => adjust_life(&mut table, args, msg.author.id),
+    };
+
+    if let Err(why) = change {
+        return format!("☢ I can't do that! ☢\n{}", why);
+    }
+
+    let summary = if table.pod.is_empty() {
+        "There's no game going on here right now. Start one with `!life start @you @them`!".to_string()
+    } else {
+        pod_summary(&table)
+    };
+
+    if !action.is_empty() {
+        if let Err(why) = tables.update(msg.channel_id, |saved| *saved = table).await {
+            return format!("☢ Something went wrong! ☢\n Error saving game: {}", why);
+        }
+    }
+
+    summary
+}
+
+fn start_pod(table: &mut MtgTable, args: &mut Args) -> Result<(), String> {
+    let starting_life = match args.parse::<i64>() {
+        Ok(life) if life > 0 => {
+            args.advance();
+            life
+        },
+        Ok(life) => return Err(format!("Everyone has to start with some life, not {}.", life)),
+        Err(_) => DEFAULT_LIFE,
+    };
+
+    let players = args
+        .iter::<UserId>()
+        .map(|player| player.map(|player| player.0).map_err(|why| format!("Error parsing player: {}", why)))
+        .collect::<Result<Vec<u64>, String>>()?;
+    if players.is_empty() {
+        return Err("Who's playing? Mention everyone in the game, like `!life start @you @them`.".to_string());
+    }
+
+    table.start_pod(&players, starting_life);
+
+    Ok(())
+}
+
+fn adjust_life(table: &mut MtgTable, args: &mut Args, author: UserId) -> Result<(), String> {
+    let player = take_player(args)?.unwrap_or(author);
+    let amount = args
+        .single::<i64>()
+        .map_err(|_| "How much should the life change? Give me something like `-3` or `+2`.".to_string())?;
+
+    let total = table.life_total(player.0).ok_or_else(|| not_playing(player))?;
+    total.life = total.life.saturating_add(amount);
+
+    Ok(())
+}
+
+fn commander_damage(table: &mut MtgTable, args: &mut Args, author: UserId) -> Result<(), String> {
+    let source = take_player(args)?.ok_or_else(|| "Whose commander dealt the damage? Like `!life cmd @them 6`.".to_string())?;
+    let amount = args
+        .single::<u32>()
+        .map_err(|_| "How much commander damage? Like `!life cmd @them 6`.".to_string())?;
+    let target = take_player(args)?.unwrap_or(author);
+
+    if table.life_total(source.0).is_none() {
+        return Err(not_playing(source));
+    }
+    let total = table.life_total(target.0).ok_or_else(|| not_playing(target))?;
+    let damage = total.commander_damage.entry(source.0).or_default();
+    *damage = damage.saturating_add(amount);
+    total.life = total.life.saturating_sub(i64::from(amount));
+
+    Ok(())
+}
+
+// Takes a player mention if that's what comes next
+fn take_player(args: &mut Args) -> Result<Option<UserId>, String> {
+    match args.current() {
+        Some(arg) if arg.starts_with("<@") => args
+            .single::<UserId>()
+            .map(Some)
+            .map_err(|why| format!("Error parsing player: {}", why)),
+        _ => Ok(None),
+    }
+}
+
+fn not_playing(player: UserId) -> String {
+    format!("{} isn't in this game.", player.mention())
+}
+
+fn pod_summary(table: &MtgTable) -> String {
+    let lines = table.pod
+        .iter()
+        .map(|total| {
+            let mut line = format!("{}: **{}**", UserId(total.player).mention(), total.life);
+            if !total.commander_damage.is_empty() {
+                let damage = total.commander_damage
+                    .iter()
+                    .map(|(source, damage)| format!("{} {}", UserId(*source).mention(), damage))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                line.push_str(&format!(" (commander damage: {})", damage));
+            }
+            if table.monarch == Some(total.player) { line.push_str(" 👑"); }
+            if total.is_out() { line.push_str(" ☠"); }
+            line
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!("Life totals:\n{}", lines)
+}
+
+// Shows or changes who holds a title that only one player can have at a time
+async fn track_holder(ctx: &Context, msg: &Message, args: Args, title: &str, holder: fn(&mut MtgTable) -> &mut Option<u64>) -> CommandResult {
+    let input = args.rest().trim().to_lowercase();
+
+    let new_holder = match input.as_str() {
+        "" => {
+            let current = {
+                let tables_data = ctx.data.read().await;
+                let tables = tables_data
+                    .get::<crate::MtgKey>()
+                    .expect("Failed to retrieve MTG tables!")
+                    .lock().await;
+                *holder(&mut tables.get(msg.channel_id))
+            };
+            let status = match current {
+                Some(user) => format!("{} has {}!", UserId(user).mention(), title),
+                None => format!("Nobody has {} right now.", title),
+            };
+            say_quietly(ctx, msg, status).await?;
+            return Ok(());
+        },
+        "none" => None,
+        "me" => Some(msg.author.id),
+        _ => match input.parse::<UserId>() {
+            Ok(user) => Some(user),
+            Err(why) => {
+                let user_error = format!("☢ That's not someone I recognize! ☢\n Error parsing user id: {}", why);
+                msg.channel_id.say(&ctx.http, user_error).await?;
+                return Ok(());
+            }
+        },
+    };
+
+    let saved = {
+        let tables_data = ctx.data.read().await;
+        let mut tables = tables_data
+            .get::<crate::MtgKey>()
+            .expect("Failed to retrieve MTG tables!")
+            .lock().await;
+        tables.update(msg.channel_id, |table| *holder(table) = new_holder.map(|user| user.0)).await
+    };
+    if let Err(why) = saved {
+        let save_error = format!("☢ Something went wrong! ☢\n Error saving game: {}", why);
+        msg.channel_id.say(&ctx.http, save_error).await?;
+        return Ok(());
+    }
+
+    let confirm = match new_holder {
+        Some(user) => format!("{} now has {}! ❤", user.mention(), title),
+        None => format!("Okay, nobody has {} now.", title),
+    };
+    say_quietly(ctx, msg, confirm).await?;
+
+    Ok(())
+}
+
+// Players are shown as mentions so everyone can tell who's who, but a change to the game shouldn't ping them all
+async fn say_quietly(ctx: &Context, msg: &Message, content: String) -> serenity::Result<Message> {
+    msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(content);
+        m.allowed_mentions(|mentions| mentions.empty_parse())
+    }).await
+}
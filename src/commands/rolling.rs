@@ -1,56 +1,1561 @@
 use serenity::{
     framework::{
         standard::{
+            Args,
             CommandResult,
             macros::{
                 command,
             },
         },
     },
-    model::channel::Message,
+    model::{
+        channel::Message,
+        guild::Member,
+        id::{
+            GuildId,
+            RoleId,
+            UserId,
+        },
+        user::User,
+    },
     prelude::*,
 };
 
+use std::convert::TryFrom;
+
+use rand::{
+    distributions::WeightedIndex,
+    prelude::*,
+};
+
+use crate::{
+    dice::{
+        fairness::{
+            FairnessReport,
+            MAX_FAIRNESS_SIDES,
+        },
+        opposed::OpposedRoll,
+        pool::Pool,
+        report::{
+            RollReport,
+            Severity,
+        },
+        roll::RepeatedRoll,
+        systems::{
+            bitd::{
+                BitdResult,
+                BitdRoll,
+            },
+            coc::{
+                self,
+                CocResult,
+                CocRoll,
+            },
+            exalted::{
+                self,
+                ExaltedRoll,
+            },
+            fate::{
+                self,
+                FateRoll,
+            },
+            l5r::{
+                self,
+                L5rDieKind,
+                L5rRoll,
+            },
+            ore::OreRoll,
+            pbta::{
+                PbtaResult,
+                PbtaRoll,
+            },
+            shadowrun::ShadowrunRoll,
+            swade::{
+                self,
+                SwadeRoll,
+            },
+            v5::{
+                self,
+                V5Roll,
+            },
+            wod::WodRoll,
+            yze::YzeRoll,
+        },
+    },
+    messaging::{
+        cleanup::track_reply,
+        render::render_report,
+        MAX_MESSAGE_LENGTH,
+    },
+    roll_observers,
+    tray::TRAY_SIZE,
+};
+
+const MAX_FIELD_LENGTH: usize = 1024;
+const MEMBERS_PAGE_SIZE: u64 = 1000;
+
+#[command]
+#[description = "Rolls dice.\n\n
+Give me the dice as `<dice>d<sides>`, like `!roll 3d6`. Add or subtract more dice and numbers to roll them all together, like `!roll 2d6+1d8+3`, and I'll show you each pool separately.\n
+Keep the highest dice with `k` or `kh`, like `!roll 4d6k3`, or the lowest with `kl`, like `!roll 2d20kl1`.
+Drop the highest or lowest dice instead with `dh` or `dl`, like `!roll 4d6dl1`, or every die showing some faces with `de`, like `!roll 6d6de1` or `!roll 6d6de[1,2]`.\n
+Add `s` or `sa` at the end to sort the dice from lowest to highest, or `sd` for highest to lowest, like `!roll 10d6sd`.
+Roll Fudge/FATE dice with `dF`, like `!roll 4dF+2`. `d%` is a d100, and `d66` or `d666` read d6s as digits, so a 3 and a 5 make 35.
+Count successes instead of adding the dice up with `t`, like `!roll 10d10t7` for every 7 or more. Add a `d` after it for dice that count twice, like `!roll 10d10t7d10`.
+Group the dice into matching sets with `m`, like `!roll 7d10m`. The roll is worth the width of the widest set, so three 7s make `3x7` and count 3.\n
+Arrays roll several pools as one: `!roll [2,3]d6` rolls 2d6 and 3d6, `!roll 2d[6,10]` rolls 2d6 and 2d10.\n
+To roll the same thing several times, put the number of times and a `#` in front: `!roll 6#4d6k3`.\n
+You can also roll one of your saved rolls by name, like `!roll attack`. See !save for how to save them."]
+async fn roll(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let report = roll_report(ctx, msg.guild_id, msg.author.id, args.rest()).await;
+    let roll = render_report(&msg.author, &report, MAX_MESSAGE_LENGTH);
+    let reply = msg.channel_id.say(&ctx.http, roll).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
 #[command]
-async fn roll(ctx: &Context, msg: &Message) -> CommandResult {
-    let roll = format!("{} I don't know how to roll dice yet!", msg.author);
-    msg.channel_id.say(&ctx.http, roll).await?;
+#[aliases("secret", "secretroll")]
+#[description = "Rolls dice in secret.\n\n
+Works just like !roll, but I'll DM you the result and only tell the channel that you made a secret roll. If the server has a GM role (see !gmrole), everyone with it gets a copy too."]
+async fn gmroll(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    // Errors go straight to the channel, there's nothing secret about them
+    let report = roll_report(ctx, msg.guild_id, msg.author.id, args.rest()).await;
+    let roll = render_report(&msg.author, &report, MAX_MESSAGE_LENGTH);
+    if report.severity == Severity::Error {
+        msg.channel_id.say(&ctx.http, roll).await?;
+        return Ok(());
+    }
+    let channel = msg.channel_id.mention();
+
+    if let Err(why) = msg.author.direct_message(&ctx.http, |m| m.content(private_roll(&format!("Your secret roll in {}:", channel), &roll))).await {
+        let dm_error = format!("☢ I couldn't DM you! ☢\n Error sending secret roll: {}", why);
+        msg.channel_id.say(&ctx.http, dm_error).await?;
+        return Ok(());
+    }
+
+    let gm_role = match msg.guild_id {
+        Some(guild_id) => {
+            let settings_data = ctx.data.read().await;
+            let registry = settings_data
+                .get::<crate::SettingsKey>()
+                .expect("Failed to retrieve guild settings!")
+                .lock().await;
+            registry.get(guild_id).gm_role.map(|role| (guild_id, RoleId(role)))
+        },
+        None => None,
+    };
+
+    let mut announcement = format!("🎲 {} made a secret roll!", msg.author);
+    if let Some((guild_id, role)) = gm_role {
+        // Listing members needs the Server Members Intent, so the roll still goes through without it
+        match role_members(ctx, guild_id, role).await {
+            Ok(members) => {
+                let header = format!("{} made a secret roll in {}:", msg.author, channel);
+                for gm in members.iter().filter(|member| member.user.id != msg.author.id) {
+                    if let Err(why) = gm.user.direct_message(&ctx.http, |m| m.content(private_roll(&header, &roll))).await {
+                        println!("Error sending secret roll to GM {}: {:?}", gm.user.id, why);
+                    }
+                }
+            },
+            Err(why) => {
+                println!("Error listing members for GM role: {:?}", why);
+                announcement.push_str(" (I couldn't find the GMs to send it to, though.)");
+            },
+        }
+    }
+
+    let reply = msg.channel_id.say(&ctx.http, announcement).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
 
     Ok(())
 }
 
+// Discord hands out members a page at a time, so keep asking until a page comes back short
+async fn role_members(ctx: &Context, guild_id: GuildId, role: RoleId) -> serenity::Result<Vec<Member>> {
+    let mut found = Vec::new();
+    let mut after = None;
+
+    loop {
+        let page = guild_id.members(&ctx.http, Some(MEMBERS_PAGE_SIZE), after).await?;
+        after = page.last().map(|member| member.user.id);
+        let last_page = (page.len() as u64) < MEMBERS_PAGE_SIZE;
+
+        found.extend(page.into_iter().filter(|member| member.roles.contains(&role)));
+        if last_page { break; }
+    }
+
+    Ok(found)
+}
+
+// Drops the header if the roll already fills the whole message
+fn private_roll(header: &str, roll: &str) -> String {
+    let full = format!("{}\n{}", header, roll);
+    if full.chars().count() > MAX_MESSAGE_LENGTH { roll.to_string() } else { full }
+}
+
+// Rolls the input, or the saved roll it names. Saved rolls take priority, names that are rolls by themselves can't
+// be saved.
+pub async fn roll_report(ctx: &Context, guild_id: Option<GuildId>, user: UserId, input: &str) -> RollReport {
+    let saved = {
+        let macro_data = ctx.data.read().await;
+        let registry = macro_data
+            .get::<crate::MacrosKey>()
+            .expect("Failed to retrieve macro registry!")
+            .lock().await;
+        registry.get(guild_id, user, input.trim()).cloned()
+    };
+    let expression = saved.as_deref().unwrap_or(input);
+
+    let report = match expression.parse::<RepeatedRoll>() {
+        Ok(repeated) => {
+            roll_observers::publish(ctx, &repeated.rolls).await;
+            RollReport::from_rolls(&repeated)
+        },
+        Err(why) => RollReport::from_error(&why),
+    };
+    match saved {
+        Some(saved) => report.annotate(format!("`{}` is `{}`", input.trim(), saved)),
+        None => report,
+    }
+}
+
 #[command]
 #[aliases("cod", "cofd")]
-async fn wod(ctx: &Context, msg: &Message) -> CommandResult {
-    let roll = format!("{} I'm not edgy enough for that yet!", msg.author);
-    msg.channel_id.say(&ctx.http, roll).await?;
+#[description = "Rolls a Chronicles of Darkness dice pool.\n\n
+Give me the size of the pool, like `!wod 7`. Every 8+ is a success and 10s are rerolled for more successes. Add `9again`, `8again` or `noagain` to change which dice get rerolled, and `rote` to reroll failed dice once.\n
+A pool of zero or less rolls a chance die, which only succeeds on a 10 and fails dramatically on a 1."]
+async fn wod(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let pool_size = match args.single::<i64>() {
+        Ok(size) => size,
+        Err(why) => {
+            let pool_error = format!("☢ How many dice should I roll? ☢\n Error parsing pool size: {}", why);
+            msg.channel_id.say(&ctx.http, pool_error).await?;
+            return Ok(());
+        }
+    };
+
+    let mut again_threshold = Some(10);
+    let mut rote = false;
+    for flag in args.iter::<String>().flatten() {
+        match flag.to_lowercase().replace('-', "").as_str() {
+            "10again" => again_threshold = Some(10),
+            "9again" => again_threshold = Some(9),
+            "8again" => again_threshold = Some(8),
+            "noagain" => again_threshold = None,
+            "rote" => rote = true,
+            _ => {
+                let flag_error = format!("☢ I don't know the `{}` rule! ☢\nTry `8again`, `9again`, `10again`, `noagain` or `rote`.", flag);
+                msg.channel_id.say(&ctx.http, flag_error).await?;
+                return Ok(());
+            }
+        }
+    }
+
+    let roll = WodRoll::new(pool_size, again_threshold, rote);
+
+    let successes = roll.successes();
+    let summary = if roll.is_dramatic_failure() {
+        format!("{} **Dramatic failure!** ☢", msg.author)
+    } else if roll.is_exceptional() {
+        format!("{} **{} successes, exceptional success!** ❤", msg.author, successes)
+    } else if successes == 0 {
+        format!("{} **Failure.**", msg.author)
+    } else {
+        format!("{} **{} success{}!**", msg.author, successes, if successes == 1 { "" } else { "es" })
+    };
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            if roll.chance_die {
+                e.title("Chance die");
+                e.field("Die", pool_field(&roll.dice), false);
+            } else {
+                if roll.rote {
+                    e.title(format!("{} dice, rote", roll.dice.dice().len()));
+                } else {
+                    e.title(format!("{} dice", roll.dice.dice().len()));
+                }
+                e.field("Dice", pool_field(&roll.dice), false);
+                if let (Some(threshold), false) = (roll.again_threshold, roll.again.is_empty()) {
+                    e.field(format!("{}-again", threshold), pool_field(&roll.again), false);
+                }
+            }
+            e.field("Successes", successes, true);
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
 
     Ok(())
 }
 
 #[command]
 #[aliases("l5roll", "rings")]
-async fn l5r(ctx: &Context, msg: &Message) -> CommandResult {
-    let roll = format!("{} I'm not weeb enough for that yet!", msg.author);
-    msg.channel_id.say(&ctx.http, roll).await?;
+#[description = "Rolls Legend of the Five Rings 5e ring and skill dice.\n\n
+Give me your ring and skill, like `!l5r 3 2`, and I'll roll that many ring dice (d6) and skill dice (d12) and keep as many dice as your ring. To keep a different number, add it at the end: `!l5r 3 2 4`.\n
+I keep the best dice for you: successes first, then opportunities, avoiding strife. Kept explosive successes roll an extra die of the same kind."]
+async fn l5r(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let (ring, skill) = match (args.single::<u32>(), args.single::<u32>()) {
+        (Ok(ring), Ok(skill)) => (ring, skill),
+        _ => {
+            let dice_error = "☢ How many dice should I roll? ☢\nGive me your ring and skill, like `!l5r 3 2`.".to_string();
+            msg.channel_id.say(&ctx.http, dice_error).await?;
+            return Ok(());
+        }
+    };
+
+    let keep = if args.is_empty() {
+        ring
+    } else {
+        match args.single::<u32>() {
+            Ok(keep) => keep,
+            Err(why) => {
+                let keep_error = format!("☢ How many dice should I keep? ☢\n Error parsing keep: {}", why);
+                msg.channel_id.say(&ctx.http, keep_error).await?;
+                return Ok(());
+            }
+        }
+    };
+
+    let roll = L5rRoll::new(ring, skill, keep);
+
+    let summary = format!(
+        "{} **{} success{}, {} opportunit{}, {} strife**",
+        msg.author,
+        roll.successes(),
+        if roll.successes() == 1 { "" } else { "es" },
+        roll.opportunities(),
+        if roll.opportunities() == 1 { "y" } else { "ies" },
+        roll.strife()
+    );
+
+    let (ring_dice, skill_dice): (Vec<_>, Vec<_>) = roll.rolled.iter().partition(|die| die.kind == L5rDieKind::Ring);
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            e.title(format!("{} ring, {} skill, keep {}", ring_dice.len(), skill_dice.len(), roll.kept.len()));
+            if !ring_dice.is_empty() {
+                e.field("Ring dice", symbols_field(&ring_dice), false);
+            }
+            if !skill_dice.is_empty() {
+                e.field("Skill dice", symbols_field(&skill_dice), false);
+            }
+            if !roll.kept.is_empty() {
+                e.field("Kept", symbols_field(&roll.kept), false);
+            }
+            if !roll.exploded.is_empty() {
+                e.field("Explosions", symbols_field(&roll.exploded), false);
+            }
+            e.field("Successes", roll.successes(), true);
+            e.field("Opportunities", roll.opportunities(), true);
+            e.field("Strife", roll.strife(), true);
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("fair", "rngcheck")]
+#[description = "Checks whether my dice are fair.\n\n
+Give me a die size, like `!fairness 20` (d6 if you don't). I'll count how often each face has come up in everyone's rolls since I last started, and run a chi-squared test to see if every face comes up about as often as it should. If there haven't been enough rolls of that die yet, I'll test a fresh batch of a thousand rolls per face instead. Even perfectly fair dice fail this about one time in twenty!"]
+async fn fairness(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let sides = if args.is_empty() { 6 } else {
+        match args.single::<u32>() {
+            Ok(sides) if (2..=MAX_FAIRNESS_SIDES).contains(&sides) => sides,
+            _ => {
+                let sides_error = format!("☢ What size of die? ☢\nI can check dice with between 2 and {} sides.", MAX_FAIRNESS_SIDES);
+                msg.channel_id.say(&ctx.http, sides_error).await?;
+                return Ok(());
+            }
+        }
+    };
+
+    let tallied = {
+        let tally_data = ctx.data.read().await;
+        let tally = tally_data.get::<crate::FaceTallyKey>().expect("Failed to retrieve face tally!").lock().await;
+        tally.report(sides)
+    };
+    let (report, source) = match tallied {
+        Some(report) => (report, "everyone's rolls since I started"),
+        // Rolling up to a hundred thousand dice takes a moment, so it's kept off the async workers
+        None => (tokio::task::spawn_blocking(move || FairnessReport::sample(sides)).await?, "a test batch, there haven't been enough real rolls yet"),
+    };
+
+    let summary = if report.looks_fair() {
+        format!("{} My d{}s look fair! ❤", msg.author, report.sides)
+    } else {
+        format!("{} My d{}s look a little off this time... try again and see if it happens twice! ☢", msg.author, report.sides)
+    };
+    let faces = report.counts
+        .iter()
+        .enumerate()
+        .map(|(face, count)| format!("{}: {}", face + 1, count))
+        .collect::<Vec<String>>();
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            e.title(format!("d{} fairness", report.sides));
+            e.field("Rolls", report.rolls(), true);
+            e.field("Counted from", source, true);
+            e.field("Chi-squared", format!("{:.2}", report.chi_squared), true);
+            e.field("Fair dice stay under", format!("{:.2}", report.critical_value), true);
+            e.field("Faces", list_field(&faces), false);
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("fudge")]
+#[description = "Rolls 4 Fudge dice for FATE.\n\n
+Give me your skill rating to add, like `!fate 3` or `!fate -1`, and I'll read the result off the ladder, like Great (+4)."]
+async fn fate(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let skill = if args.is_empty() { 0 } else {
+        match args.single::<i64>() {
+            Ok(skill) => skill,
+            Err(why) => {
+                let skill_error = format!("☢ That's not a skill rating I recognize! ☢\n Error parsing skill: {}", why);
+                msg.channel_id.say(&ctx.http, skill_error).await?;
+                return Ok(());
+            }
+        }
+    };
+
+    let roll = FateRoll::new(skill);
+
+    let total = roll.total();
+    let modifier = match roll.skill {
+        0 => String::new(),
+        skill if skill > 0 => format!(" + {}", skill),
+        skill => format!(" - {}", skill.unsigned_abs()),
+    };
+    let fate = format!("{} You rolled {}{} = **{} ({:+})**", msg.author, roll.dice, modifier, fate::ladder(total), total);
+    let reply = msg.channel_id.say(&ctx.http, fate).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("matches", "sets")]
+#[description = "Rolls a One Roll Engine pool and finds the matching sets.\n\n
+Give me the number of d10s, like `!ore 7`, or other dice, like `!ore 4d6`. Dice that show the same face make a set, written width x height: `3x7` is three 7s. The widest set is listed first."]
+async fn ore(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let input = args.rest().trim();
+    let parsed = match input.parse::<u32>() {
+        Ok(count) => Ok(OreRoll::new(count)),
+        Err(_) => input.parse::<Pool>().map(OreRoll::from_pool),
+    };
+    let roll = match parsed {
+        Ok(roll) => roll,
+        Err(why) => {
+            let pool_error = format!("☢ How many dice should I roll? ☢\n Error parsing pool: {}", why);
+            msg.channel_id.say(&ctx.http, pool_error).await?;
+            return Ok(());
+        }
+    };
+
+    let sets = roll.sets();
+    let summary = match sets.first() {
+        Some(widest) => format!("{} **{}!**", msg.author, widest),
+        None => format!("{} **No matches!**", msg.author),
+    };
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            e.title(format!("{} dice", roll.dice.dice().len()));
+            e.field("Dice", pool_field(&roll.dice.sorted(true)), false);
+            if !sets.is_empty() {
+                e.field("Sets", list_field(&sets), false);
+            }
+            let loose = roll.loose();
+            if !loose.is_empty() {
+                e.field("Loose dice", list_field(&loose), false);
+            }
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
 
     Ok(())
 }
 
 #[command]
 #[aliases("sr")]
-async fn sroll(ctx: &Context, msg: &Message) -> CommandResult {
-    let roll = format!("{} I'm not shady enough for that yet!", msg.author);
-    msg.channel_id.say(&ctx.http, roll).await?;
+#[description = "Rolls a Shadowrun dice pool.\n\n
+Give me the size of the pool, like `!sr 12`. Every 5 or 6 is a hit, and if half or more of the dice come up 1 it's a glitch. Add a limit to cap the hits, like `!sr 12 l5`, and `edge` to make 6s explode (which also ignores the limit)."]
+async fn sroll(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let pool_size = match args.single::<u32>() {
+        Ok(size) => size,
+        Err(why) => {
+            let pool_error = format!("☢ How many dice should I roll? ☢\n Error parsing pool size: {}", why);
+            msg.channel_id.say(&ctx.http, pool_error).await?;
+            return Ok(());
+        }
+    };
+
+    let mut limit = None;
+    let mut used_edge = false;
+    for option in args.iter::<String>().flatten() {
+        let option = option.to_lowercase();
+        let limit_str = option.trim_start_matches("limit").trim_start_matches('l');
+        if option == "edge" || option == "e" {
+            used_edge = true;
+        } else if let Ok(value) = limit_str.parse::<u32>() {
+            limit = Some(value);
+        } else {
+            let option_error = format!("☢ I don't know what `{}` means! ☢\nTry a limit like `l5` or `edge`.", option);
+            msg.channel_id.say(&ctx.http, option_error).await?;
+            return Ok(());
+        }
+    }
+
+    let roll = ShadowrunRoll::new(pool_size, limit, used_edge);
+
+    let hits = roll.hits();
+    let summary = if roll.is_critical_glitch() {
+        format!("{} **Critical glitch!** ☢☢☢", msg.author)
+    } else if roll.is_glitch() {
+        format!("{} **{} hit{}, but it's a glitch!** ☢", msg.author, hits, if hits == 1 { "" } else { "s" })
+    } else {
+        format!("{} **{} hit{}!**", msg.author, hits, if hits == 1 { "" } else { "s" })
+    };
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            e.title(format!("{} dice", roll.dice.dice().len()));
+            e.field("Dice", pool_field(&roll.dice), false);
+            if !roll.edge.is_empty() {
+                e.field("Exploding 6s", pool_field(&roll.edge), false);
+            }
+            e.field("Hits", hits, true);
+            if let (Some(limit), false) = (roll.limit, roll.used_edge) {
+                e.field("Limit", limit, true);
+            }
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("sw", "savage")]
+#[description = "Rolls a Savage Worlds trait test.\n\n
+Give me the trait die, like `!swade d8`, and I'll roll it with a d6 wild die and keep the higher. Both dice ace (roll again and add on their highest face). Add a modifier like `+2` or `-1`, a target number like `tn6` (it's 4 if you don't), and `extra` to roll without the wild die.\n
+Every 4 over the target number is a raise, and both dice coming up 1 is a critical failure."]
+async fn swade(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let trait_str = args.single::<String>().unwrap_or_default().to_lowercase();
+    let trait_sides = match trait_str.trim_start_matches('d').parse::<u32>() {
+        Ok(sides) if swade::TRAIT_DICE.contains(&sides) => sides,
+        _ => {
+            let trait_error = "☢ Which trait die should I roll? ☢\nTry `d4`, `d6`, `d8`, `d10` or `d12`.".to_string();
+            msg.channel_id.say(&ctx.http, trait_error).await?;
+            return Ok(());
+        }
+    };
+
+    let mut modifier: i64 = 0;
+    let mut target = swade::DEFAULT_TARGET;
+    let mut wild_card = true;
+    for option in args.iter::<String>().flatten() {
+        let option = option.to_lowercase();
+        if option == "extra" || option == "nowild" {
+            wild_card = false;
+        } else if let Some(Ok(value)) = option.strip_prefix("tn").map(str::parse::<i64>) {
+            target = value;
+        } else if let Ok(value) = option.parse::<i64>() {
+            modifier = modifier.saturating_add(value);
+        } else {
+            let option_error = format!("☢ I don't know what `{}` means! ☢\nTry a modifier like `+2`, a target number like `tn6` or `extra`.", option);
+            msg.channel_id.say(&ctx.http, option_error).await?;
+            return Ok(());
+        }
+    }
+
+    let roll = SwadeRoll::new(trait_sides, wild_card, modifier, target);
+
+    let total = roll.total();
+    let summary = if roll.is_critical_failure() {
+        format!("{} **Critical failure!** ☢☢☢", msg.author)
+    } else if !roll.is_success() {
+        format!("{} **{}, failure!** ☢", msg.author, total)
+    } else {
+        match roll.raises() {
+            0 => format!("{} **{}, success!**", msg.author, total),
+            1 => format!("{} **{}, success with a raise!**", msg.author, total),
+            raises => format!("{} **{}, success with {} raises!**", msg.author, total, raises),
+        }
+    };
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            e.title(format!("d{} against {}", trait_sides, roll.target));
+            let kept = |kept: bool| if kept { " (kept)" } else { "" };
+            e.field(format!("Trait die{}", kept(!roll.wild_die_kept())), pool_field(&roll.trait_die), true);
+            if let Some(wild) = &roll.wild_die {
+                e.field(format!("Wild die{}", kept(roll.wild_die_kept())), pool_field(wild), true);
+            }
+            if roll.modifier != 0 {
+                e.field("Modifier", format!("{:+}", roll.modifier), true);
+            }
+            e.field("Total", total, true);
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("vtm")]
+#[description = "Rolls a Vampire: the Masquerade 5e dice pool.\n\n
+Give me the size of the pool and your Hunger, like `!v5 7 2`. Every 6+ is a success and every pair of 10s is a critical worth four. Hunger dice replace that many dice in the pool, up to 5. Add a difficulty at the end, like `!v5 7 2 3`, and I'll tell you if you passed.\n
+A critical with a 10 on a hunger die is messy, and failing with a 1 on a hunger die is a bestial failure."]
+async fn v5(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let (pool_size, hunger) = match (args.single::<u32>(), args.single::<u32>()) {
+        (Ok(pool_size), Ok(hunger)) => (pool_size, hunger),
+        _ => {
+            let dice_error = "☢ How many dice should I roll? ☢\nGive me your pool and Hunger, like `!v5 7 2`.".to_string();
+            msg.channel_id.say(&ctx.http, dice_error).await?;
+            return Ok(());
+        }
+    };
+    let difficulty = match args.single::<u32>() {
+        Ok(difficulty) => Some(difficulty),
+        Err(_) if args.is_empty() => None,
+        Err(why) => {
+            let difficulty_error = format!("☢ That's not a difficulty I recognize! ☢\n Error parsing difficulty: {}", why);
+            msg.channel_id.say(&ctx.http, difficulty_error).await?;
+            return Ok(());
+        }
+    };
+
+    if hunger > v5::MAX_HUNGER {
+        let hunger_error = format!("☢ Hunger only goes up to {}! ☢", v5::MAX_HUNGER);
+        msg.channel_id.say(&ctx.http, hunger_error).await?;
+        return Ok(());
+    }
+
+    let roll = V5Roll::new(pool_size, hunger, difficulty);
+
+    let size = roll.dice.dice().len() + roll.hunger.dice().len();
+    let successes = roll.successes();
+    let plural = if successes == 1 { "" } else { "es" };
+    let summary = if roll.is_messy_critical() {
+        format!("{} **{} success{}, messy critical!** 🩸", msg.author, successes, plural)
+    } else if roll.is_critical() {
+        format!("{} **{} success{}, critical!** ❤", msg.author, successes, plural)
+    } else if roll.is_bestial_failure() {
+        format!("{} **{} success{}, bestial failure!** ☢", msg.author, successes, plural)
+    } else if !roll.is_success() {
+        format!("{} **{} success{}, failure.**", msg.author, successes, plural)
+    } else {
+        format!("{} **{} success{}!**", msg.author, successes, plural)
+    };
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            match roll.difficulty {
+                Some(difficulty) => e.title(format!("{} dice against difficulty {}", size, difficulty)),
+                None => e.title(format!("{} dice", size)),
+            };
+            if !roll.dice.is_empty() {
+                e.field("Dice", pool_field(&roll.dice), false);
+            }
+            if !roll.hunger.is_empty() {
+                e.field("Hunger dice", pool_field(&roll.hunger), false);
+            }
+            e.field("Successes", successes, true);
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("blades", "fitd")]
+#[description = "Rolls a Blades in the Dark action.\n\n
+Give me the number of dice, like `!bitd 3`, and I'll roll that many d6 and keep the highest: 6 is a success, 4 or 5 is a partial success and 1-3 is a failure. Two or more 6s is a critical.\n
+With zero dice I'll roll two and keep the lower one, and that can't be a critical."]
+async fn bitd(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let count = match args.single::<u32>() {
+        Ok(count) => count,
+        Err(why) => {
+            let dice_error = format!("☢ How many dice should I roll? ☢\n Error parsing dice: {}", why);
+            msg.channel_id.say(&ctx.http, dice_error).await?;
+            return Ok(());
+        }
+    };
+
+    let roll = BitdRoll::new(count);
+
+    let result = roll.result();
+    let summary = match result {
+        BitdResult::Critical => format!("{} **{}!** ❤", msg.author, result),
+        BitdResult::Failure => format!("{} **{}.** ☢", msg.author, result),
+        _ => format!("{} **{}!**", msg.author, result),
+    };
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            if roll.zero_dice {
+                e.title("Zero dice");
+            } else {
+                e.title(format!("{} dice", roll.dice.dice().len()));
+            }
+            e.field("Dice", pool_field(&roll.dice), false);
+            e.field("Kept", roll.kept.total(), true);
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("myz", "fbl")]
+#[description = "Rolls a Year Zero Engine test, like Mutant: Year Zero or Forbidden Lands.\n\n
+Give me your attribute, skill and gear dice, like `!yze 3 2 1`, and I'll roll that many d6 in each. Every 6 is a success, and 1s on attribute and gear dice are banes. Skill and gear are optional.\n
+`!yze push` pushes your last roll in this channel: every die that isn't a 6 or a 1 gets rerolled. To push an earlier roll, reply to my message showing it with `!yze push`. You can only push a roll once."]
+async fn yze(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let new_roll = if args.current().map(|arg| arg.eq_ignore_ascii_case("push")).unwrap_or(false) {
+        None
+    } else {
+        let mut counts = Vec::new();
+        for count in args.iter::<u32>() {
+            match count {
+                Ok(count) => counts.push(count),
+                Err(why) => {
+                    let dice_error = format!("☢ How many dice should I roll? ☢\n Error parsing dice: {}", why);
+                    msg.channel_id.say(&ctx.http, dice_error).await?;
+                    return Ok(());
+                }
+            }
+        }
+
+        match counts.as_slice() {
+            counts if counts.iter().all(|&count| count == 0) && !counts.is_empty() => {
+                let dice_error = "☢ I can't roll zero dice! ☢".to_string();
+                msg.channel_id.say(&ctx.http, dice_error).await?;
+                return Ok(());
+            },
+            [attribute] => Some(YzeRoll::new(*attribute, 0, 0)),
+            [attribute, skill] => Some(YzeRoll::new(*attribute, *skill, 0)),
+            [attribute, skill, gear] => Some(YzeRoll::new(*attribute, *skill, *gear)),
+            _ => {
+                let dice_error = "☢ How many dice should I roll? ☢\nGive me your attribute, skill and gear dice, like `!yze 3 2 1`.".to_string();
+                msg.channel_id.say(&ctx.http, dice_error).await?;
+                return Ok(());
+            },
+        }
+    };
+
+    let roll = match new_roll {
+        Some(roll) => Ok(roll),
+        None => {
+            let replied_to = msg.message_reference.as_ref().and_then(|reference| reference.message_id);
+            let yze_data = ctx.data.read().await;
+            let mut tray = yze_data
+                .get::<crate::YzeTrayKey>()
+                .expect("Failed to retrieve yze tray!")
+                .lock().await;
+
+            match tray.find_mut(msg.channel_id, msg.author.id, replied_to) {
+                Some(entry) if entry.roller != msg.author.id => Err("☢ That's not your roll! ☢\nYou can only push your own rolls.".to_string()),
+                Some(entry) if entry.pushed || entry.roll.pushed => Err("☢ You already pushed that roll! ☢\nMake a new roll with `!yze` first.".to_string()),
+                Some(entry) => {
+                    entry.pushed = true;
+                    Ok(entry.roll.push())
+                },
+                None if replied_to.is_some() => Err(format!("☢ I don't remember that roll! ☢\nI only remember the last {} `!yze` rolls in each channel since I last started.", TRAY_SIZE)),
+                None => Err("☢ There's nothing to push! ☢\nYou haven't made a `!yze` roll in this channel yet.".to_string()),
+            }
+        },
+    };
+    let roll = match roll {
+        Ok(roll) => roll,
+        Err(push_error) => {
+            msg.channel_id.say(&ctx.http, push_error).await?;
+            return Ok(());
+        }
+    };
+
+    let successes = roll.successes();
+    let summary = match successes {
+        0 => format!("{} **Failure.**", msg.author),
+        1 => format!("{} **1 success!**", msg.author),
+        _ => format!("{} **{} successes!**", msg.author, successes),
+    };
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            if roll.pushed {
+                e.title("Pushed roll");
+            } else {
+                e.title("Roll");
+            }
+            for (name, pool) in [("Attribute dice", &roll.attribute), ("Skill dice", &roll.skill), ("Gear dice", &roll.gear)] {
+                if !pool.is_empty() {
+                    e.field(name, pool_field(pool), false);
+                }
+            }
+            e.field("Successes", successes, true);
+            e.field("Attribute banes", roll.attribute_banes(), true);
+            if !roll.gear.is_empty() {
+                e.field("Gear banes", roll.gear_banes(), true);
+            }
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    // The roll goes in the tray under my reply, so it can be pushed by replying to it
+    let yze_data = ctx.data.read().await;
+    let mut tray = yze_data
+        .get::<crate::YzeTrayKey>()
+        .expect("Failed to retrieve yze tray!")
+        .lock().await;
+    tray.record(msg.channel_id, reply.id, msg.author.id, roll);
+
+    Ok(())
+}
+
+#[command]
+#[aliases("cthulhu")]
+#[description = "Rolls a Call of Cthulhu 7e skill check.\n\n
+Give me the skill value, like `!coc 60`, and I'll roll a d100 and tell you how well you did: critical on a 01, extreme at a fifth of your skill, hard at half, regular at your skill, and a fumble on 100 (or 96+ if your skill is under 50).\n
+Add `b` for a bonus die or `p` for a penalty die, like `!coc 60 b`. Use `bb` or `pp` for two. Bonus and penalty dice cancel each other out."]
+async fn coc(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let skill = match args.single::<u32>() {
+        Ok(skill) => skill,
+        Err(why) => {
+            let skill_error = format!("☢ What's the skill value? ☢\n Error parsing skill: {}", why);
+            msg.channel_id.say(&ctx.http, skill_error).await?;
+            return Ok(());
+        }
+    };
+
+    let mut extra_dice: i32 = 0;
+    for option in args.iter::<String>().flatten() {
+        let option = option.to_lowercase();
+        match parse_coc_dice(&option) {
+            Some(dice) => match extra_dice.checked_add(dice) {
+                Some(total) => extra_dice = total,
+                None => {
+                    let dice_error = format!("☢ That's too many bonus or penalty dice! ☢\nI can only roll up to {} at once.", coc::MAX_EXTRA_DICE);
+                    msg.channel_id.say(&ctx.http, dice_error).await?;
+                    return Ok(());
+                },
+            },
+            None => {
+                let option_error = format!("☢ I don't know what `{}` means! ☢\nTry `b` or `bb` for bonus dice and `p` or `pp` for penalty dice.", option);
+                msg.channel_id.say(&ctx.http, option_error).await?;
+                return Ok(());
+            }
+        }
+    }
+    if extra_dice.unsigned_abs() > coc::MAX_EXTRA_DICE {
+        let dice_error = format!("☢ That's too many bonus or penalty dice! ☢\nI can only roll up to {} at once.", coc::MAX_EXTRA_DICE);
+        msg.channel_id.say(&ctx.http, dice_error).await?;
+        return Ok(());
+    }
+
+    let roll = CocRoll::new(skill, extra_dice);
+
+    let result = roll.result();
+    let summary = match result {
+        CocResult::Critical => format!("{} **{}: {}!** ❤", msg.author, roll.value(), result),
+        CocResult::Failure => format!("{} **{}: {}.**", msg.author, roll.value(), result),
+        CocResult::Fumble => format!("{} **{}: {}!** ☢", msg.author, roll.value(), result),
+        _ => format!("{} **{}: {}!**", msg.author, roll.value(), result),
+    };
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            e.title(format!("Skill {} ({} / {})", skill, skill / 2, skill / 5));
+            let tens = roll.tens.dice().iter().map(|die| format!("{:02}", die.result % 10 * 10)).collect::<Vec<String>>();
+            let tens_name = match roll.extra_dice {
+                0 => "Tens die".to_string(),
+                bonus if bonus > 0 => format!("Tens dice ({} bonus)", bonus),
+                penalty => format!("Tens dice ({} penalty)", -penalty),
+            };
+            e.field(tens_name, list_field(&tens), true);
+            e.field("Units die", roll.units.total() % 10, true);
+            e.field("Margin", format!("{:+}", roll.margin()), true);
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+// `b`, `bb` or `b2` for bonus dice, `p`, `pp` or `p2` for penalty dice
+fn parse_coc_dice(option: &str) -> Option<i32> {
+    let sign = match option.chars().next()? {
+        'b' => 1,
+        'p' => -1,
+        _ => return None,
+    };
+    let rest = &option[1..];
+
+    // Only plain digits after the letter, so `b-3` can't turn into penalty dice
+    let count = if rest.chars().all(|c| option.starts_with(c)) {
+        i32::try_from(rest.len()).ok()?.checked_add(1)?
+    } else if rest.chars().all(|c| c.is_ascii_digit()) {
+        rest.parse::<i32>().ok()?
+    } else {
+        return None;
+    };
+    if count == 0 { return None; }
+
+    count.checked_mul(sign)
+}
+
+#[command]
+#[aliases("move", "apoc")]
+#[description = "Rolls a Powered by the Apocalypse move.\n\n
+Give me your modifier, like `!pbta +2`, and I'll roll 2d6 and add it: 10+ is a strong hit, 7-9 is a weak hit and 6 or less is a miss. Without a modifier I'll just roll 2d6.\n
+Add `adv` to roll 3d6 and keep the highest two, or `dis` to keep the lowest two, like `!pbta +1 adv`."]
+async fn pbta(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let mut modifier: i64 = 0;
+    let mut advantage = 0;
+    for option in args.raw() {
+        let option = option.to_lowercase();
+        match option.as_str() {
+            "adv" | "advantage" => advantage += 1,
+            "dis" | "disadvantage" => advantage -= 1,
+            _ => match option.parse::<i64>() {
+                Ok(value) => modifier = modifier.saturating_add(value),
+                Err(_) => {
+                    let option_error = format!("☢ I don't know what `{}` means! ☢\nTry a modifier like `+2`, `adv` or `dis`.", option);
+                    msg.channel_id.say(&ctx.http, option_error).await?;
+                    return Ok(());
+                }
+            },
+        }
+    }
+
+    let roll = PbtaRoll::new(modifier, advantage);
+
+    let result = roll.result();
+    let summary = match result {
+        PbtaResult::StrongHit => format!("{} **{}, {}!** ❤", msg.author, roll.total(), result),
+        PbtaResult::WeakHit => format!("{} **{}, {}!**", msg.author, roll.total(), result),
+        PbtaResult::Miss => format!("{} **{}, {}.** ☢", msg.author, roll.total(), result),
+    };
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            match advantage.signum() {
+                1 => e.title("3d6, keeping the highest two"),
+                -1 => e.title("3d6, keeping the lowest two"),
+                _ => e.title("2d6"),
+            };
+            e.field("Dice", pool_field(&roll.dice), true);
+            if roll.dice.dice().len() != roll.kept.dice().len() {
+                e.field("Kept", pool_field(&roll.kept), true);
+            }
+            e.field("Modifier", format!("{:+}", roll.modifier), true);
+            e.field("Total", roll.total(), true);
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
 
     Ok(())
 }
 
 #[command]
 #[aliases("ex")]
-async fn exroll(ctx: &Context, msg: &Message) -> CommandResult {
-    let roll = format!("{} I'm not epic enough for that yet!", msg.author);
-    msg.channel_id.say(&ctx.http, roll).await?;
+#[description = "Rolls an Exalted 3e dice pool.\n\n
+Give me the size of the pool, like `!ex 8`. Every 7+ is a success and 10s count double. Change the target number with `t`, like `t6`, and the double threshold with `d`, like `d9`. Add `stunt` for two bonus dice."]
+async fn exroll(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let pool_size = match args.single::<u32>() {
+        Ok(size) => size,
+        Err(why) => {
+            let pool_error = format!("☢ How many dice should I roll? ☢\n Error parsing pool size: {}", why);
+            msg.channel_id.say(&ctx.http, pool_error).await?;
+            return Ok(());
+        }
+    };
+
+    let mut target = exalted::DEFAULT_TARGET;
+    let mut double = exalted::DEFAULT_DOUBLE;
+    let mut stunt = false;
+    for option in args.iter::<String>().flatten() {
+        let option = option.to_lowercase();
+        let threshold = option.get(1..).and_then(|value| value.parse::<u32>().ok()).filter(|value| (1..=10).contains(value));
+        match (option.chars().next(), threshold) {
+            _ if option == "stunt" => stunt = true,
+            (Some('t'), Some(value)) => target = value,
+            (Some('d'), Some(value)) => double = value,
+            _ => {
+                let option_error = format!("☢ I don't know what `{}` means! ☢\nTry a target like `t6`, a double threshold like `d9`, or `stunt`.", option);
+                msg.channel_id.say(&ctx.http, option_error).await?;
+                return Ok(());
+            }
+        }
+    }
+    if double < target {
+        let double_error = format!("☢ Doubles need to be at least the target! ☢\n`d{}` can't go with `t{}`.", double, target);
+        msg.channel_id.say(&ctx.http, double_error).await?;
+        return Ok(());
+    }
+
+    let roll = ExaltedRoll::new(pool_size, target, double, stunt);
+
+    let successes = roll.successes();
+    let summary = if roll.is_botch() {
+        format!("{} **Botch!** ☢", msg.author)
+    } else {
+        format!("{} **{} success{}!**", msg.author, successes, if successes == 1 { "" } else { "es" })
+    };
+
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(summary);
+        m.embed(|e| {
+            e.title(format!(
+                "{} dice{}, target {}, double {}",
+                roll.dice.dice().len(),
+                if roll.stunt { " (with stunt)" } else { "" },
+                roll.target,
+                roll.double
+            ));
+            e.field("Dice", pool_field(&roll.dice), false);
+            e.field("Successes", successes, true);
+
+            e
+        });
+        m
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+#[command]
+#[aliases("odds")]
+#[description = "Calculates the chance of getting enough successes from a pool of dice.\n\n
+Give me the pool as `<dice>d<sides>t<target>` and the successes you need after `>=`, like `!chance 8d10t7 >= 3`. If you leave off the successes, I'll assume you need at least one.\n\n
+You can mix dice with different sides or targets by adding them together, like `!chance 4d10t7 + 3d6t5 >= 3`. Mixed pools are simulated, so their chance is close but not exact."]
+async fn chance(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let max_dice = {
+        let config_data = ctx.data.read().await;
+        let cfg = config_data.get::<crate::ConfigKey>().expect("Failed to retrieve config!");
+        cfg.limits.max_chance_dice
+    };
+
+    let (pools, needed) = match parse_chance(args.rest(), max_dice) {
+        Ok(parsed) => parsed,
+        Err(why) => {
+            let parse_error = format!("☢ I don't understand that pool! ☢\n Error parsing pool: {}", why);
+            msg.channel_id.say(&ctx.http, parse_error).await?;
+            return Ok(());
+        }
+    };
+
+    let (chance, how) = match exact_chance(&pools, needed) {
+        Some(chance) => (chance, String::new()),
+        None => {
+            // Simulating a big mixed pool takes a moment, so it's kept off the async workers
+            let to_simulate = pools.clone();
+            let chance = tokio::task::spawn_blocking(move || simulated_chance(&to_simulate, needed)).await?;
+            (chance, format!(" (simulated from {} rolls)", SIMULATED_TRIALS))
+        }
+    };
+
+    let odds = format!(
+        "{} The chance of getting at least {} success{} on {} is **{:.2}%**{}. (Expected successes: {:.2})",
+        msg.author,
+        needed,
+        if needed == 1 { "" } else { "es" },
+        pools.iter().map(SuccessPool::to_string).collect::<Vec<String>>().join(" and "),
+        chance * 100.0,
+        how,
+        pools.iter().map(SuccessPool::expected_successes).sum::<f64>()
+    );
+    let reply = msg.channel_id.say(&ctx.http, odds).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+// Enough rolls to get a mixed pool's chance to within about a percent
+const SIMULATED_TRIALS: u32 = 20_000;
+
+#[derive(Debug, Clone)]
+struct SuccessPool {
+    dice: u32,
+    sides: u32,
+    target: u32,
+}
+
+impl SuccessPool {
+    // Chance of a single die showing the target number or higher
+    fn success_chance(&self) -> f64 {
+        f64::from(self.sides - self.target + 1) / f64::from(self.sides)
+    }
+
+    fn expected_successes(&self) -> f64 {
+        f64::from(self.dice) * self.success_chance()
+    }
+
+    // Every die succeeds independently with the same chance, so the number of successes is binomially distributed.
+    // Each term is worked out in log space, since for big pools the coefficients overflow an f64 and the powers
+    // underflow it.
+    fn chance_of_at_least(&self, needed: u32) -> f64 {
+        if needed > self.dice { return 0.0; }
+
+        let p = self.success_chance();
+        if p >= 1.0 { return 1.0; }
+
+        let chance = (needed..=self.dice)
+            .map(|k| (ln_binomial(self.dice, k) + f64::from(k) * p.ln() + f64::from(self.dice - k) * (1.0 - p).ln()).exp())
+            .sum::<f64>();
+
+        chance.min(1.0)
+    }
+}
+
+impl std::fmt::Display for SuccessPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}d{} with a target of {}", self.dice, self.sides, self.target)
+    }
+}
+
+fn ln_binomial(n: u32, k: u32) -> f64 {
+    let k = k.min(n - k);
+    (0..k).map(|i| f64::from(n - i).ln() - f64::from(i + 1).ln()).sum()
+}
+
+// Groups of dice that all succeed just as often are really one big binomial pool, so their chance can be worked
+// out exactly. Mixed groups give None and have to be simulated.
+fn exact_chance(pools: &[SuccessPool], needed: u32) -> Option<f64> {
+    let first = pools.first()?;
+    let successes = |pool: &SuccessPool| u64::from(pool.sides - pool.target + 1);
+    let same_chance = pools
+        .iter()
+        .all(|pool| successes(pool) * u64::from(first.sides) == successes(first) * u64::from(pool.sides));
+    if !same_chance { return None; }
+
+    let combined = SuccessPool { dice: pools.iter().map(|pool| pool.dice).sum(), ..*first };
+    Some(combined.chance_of_at_least(needed))
+}
+
+fn simulated_chance(pools: &[SuccessPool], needed: u32) -> f64 {
+    let mut rng = rand::thread_rng();
+    let hits = (0..SIMULATED_TRIALS)
+        .filter(|_| {
+            let successes = pools
+                .iter()
+                .map(|pool| (0..pool.dice).filter(|_| rng.gen_range(1..=pool.sides) >= pool.target).count() as u32)
+                .sum::<u32>();
+            successes >= needed
+        })
+        .count();
+
+    hits as f64 / f64::from(SIMULATED_TRIALS)
+}
+
+fn parse_chance(input: &str, max_dice: u32) -> Result<(Vec<SuccessPool>, u32), String> {
+    let input: String = input.split_whitespace().collect::<String>().to_lowercase();
+
+    let (pools_str, needed_str) = match input.split_once(">=") {
+        Some((pools, needed)) => (pools, needed),
+        None => (input.as_str(), "1"),
+    };
+
+    let pools = pools_str.split('+').map(parse_success_pool).collect::<Result<Vec<SuccessPool>, String>>()?;
+    let needed = needed_str.parse::<u32>().map_err(|why| format!("invalid number of successes `{}`: {}", needed_str, why))?;
+
+    let total_dice = pools.iter().map(|pool| u64::from(pool.dice)).sum::<u64>();
+    if total_dice > u64::from(max_dice) {
+        return Err(format!("number of dice must be between 1 and {}", max_dice));
+    }
+
+    Ok((pools, needed))
+}
+
+fn parse_success_pool(pool_str: &str) -> Result<SuccessPool, String> {
+    let (dice_str, rest) = pool_str.split_once('d').ok_or("missing `d` between dice and sides")?;
+    let (sides_str, target_str) = rest.split_once('t').ok_or("missing `t` before the target number")?;
+
+    let dice = dice_str.parse::<u32>().map_err(|why| format!("invalid number of dice `{}`: {}", dice_str, why))?;
+    let sides = sides_str.parse::<u32>().map_err(|why| format!("invalid number of sides `{}`: {}", sides_str, why))?;
+    let target = target_str.parse::<u32>().map_err(|why| format!("invalid target number `{}`: {}", target_str, why))?;
+
+    if dice == 0 {
+        return Err("every group needs at least one die".to_string());
+    }
+    if sides == 0 {
+        return Err("dice need at least one side".to_string());
+    }
+    if target == 0 || target > sides {
+        return Err(format!("target number must be between 1 and {}", sides));
+    }
+
+    Ok(SuccessPool { dice, sides, target })
+}
+
+#[command]
+#[aliases("pick", "choose")]
+#[description = "Picks one option at random, with weights.\n\n
+Give me the options as `name:weight`, like `!weighted sword:3 axe:1 bow:2`, and I'll pick one and tell you how likely each option was. Options without a weight count as weight 1."]
+async fn weighted(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let options = match parse_weighted(args.rest()) {
+        Ok(options) => options,
+        Err(why) => {
+            let parse_error = format!("☢ I don't understand those options! ☢\n Error parsing options: {}", why);
+            msg.channel_id.say(&ctx.http, parse_error).await?;
+            return Ok(());
+        }
+    };
+
+    let picked = {
+        let weights = WeightedIndex::new(options.iter().map(|(_, weight)| f64::from(*weight)))?;
+        let mut rng = thread_rng();
+        weights.sample(&mut rng)
+    };
+
+    let pick = pick_reply(&msg.author, &options, picked);
+    let reply = msg.channel_id.say(&ctx.http, pick).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+// Every option's odds if they fit, otherwise just the odds of the one that was picked
+fn pick_reply(author: &User, options: &[(String, u32)], picked: usize) -> String {
+    let total: f64 = options.iter().map(|(_, weight)| f64::from(*weight)).sum();
+    let percent = |weight: u32| f64::from(weight) / total * 100.0;
+    let (name, weight) = &options[picked];
+
+    let odds = options
+        .iter()
+        .map(|(name, weight)| format!("{} {:.2}%", name, percent(*weight)))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let pick = format!("{} I picked **{}**! ({})", author, name, odds);
+    if pick.chars().count() <= MAX_MESSAGE_LENGTH { return pick; }
+
+    let odds = format!("({:.2}% chance, out of {} options)", percent(*weight), options.len());
+    let pick = format!("{} I picked **{}**! {}", author, name, odds);
+    if pick.chars().count() <= MAX_MESSAGE_LENGTH { return pick; }
+
+    // A single name can still be nearly as long as a whole message
+    let room = MAX_MESSAGE_LENGTH.saturating_sub(format!("{} I picked **…**! {}", author, odds).chars().count());
+    format!("{} I picked **{}…**! {}", author, name.chars().take(room).collect::<String>(), odds)
+}
+
+fn parse_weighted(input: &str) -> Result<Vec<(String, u32)>, String> {
+    let mut options = Vec::new();
+
+    for option in input.split_whitespace() {
+        let (name, weight) = match option.rsplit_once(':') {
+            Some((name, weight_str)) => {
+                let weight = weight_str.parse::<u32>().map_err(|why| format!("invalid weight `{}` for `{}`: {}", weight_str, name, why))?;
+                (name, weight)
+            },
+            None => (option, 1),
+        };
+
+        if name.is_empty() {
+            return Err(format!("option `{}` has no name", option));
+        }
+        if weight == 0 {
+            return Err(format!("weight for `{}` must be at least 1", name));
+        }
+
+        options.push((name.to_string(), weight));
+    }
+
+    if options.is_empty() {
+        return Err("give me at least one option to pick from".to_string());
+    }
+
+    Ok(options)
+}
+
+#[command]
+#[aliases("barter")]
+#[description = "Haggles with a shopkeeper over a price.\n\n
+Give me the asking price and your modifier, like `!haggle 250 +3`. You and the shopkeeper make an opposed roll, and every point you win or lose by moves the price."]
+async fn haggle(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let price = match args.single::<f64>() {
+        Ok(price) if price.is_finite() && price > 0.0 => price,
+        _ => {
+            let price_error = "☢ How much does it cost? ☢\nGive me the asking price first, like `!haggle 250 +3`.".to_string();
+            msg.channel_id.say(&ctx.http, price_error).await?;
+            return Ok(());
+        }
+    };
+
+    let modifier = if args.is_empty() {
+        0
+    } else {
+        match args.single::<i64>() {
+            Ok(modifier) => modifier,
+            Err(why) => {
+                let mod_error = format!("☢ That's not a modifier I recognize! ☢\n Error parsing modifier: {}", why);
+                msg.channel_id.say(&ctx.http, mod_error).await?;
+                return Ok(());
+            }
+        }
+    };
+
+    let (dice, percent_per_point, max_percent) = {
+        let config_data = ctx.data.read().await;
+        let cfg = config_data.get::<crate::ConfigKey>().expect("Failed to retrieve config!");
+        (cfg.haggle.dice.clone(), cfg.haggle.percent_per_point, cfg.haggle.max_percent)
+    };
+
+    let opposed = OpposedRoll::new(&dice, modifier, 0)?;
+
+    // Winning the roll lowers the price, losing it raises the price
+    let percent = ((opposed.margin() as f64 * percent_per_point).clamp(-max_percent, max_percent) * 100.0).round() / 100.0;
+    let final_price = (price * (1.0 - percent / 100.0) * 100.0).round() / 100.0;
+
+    let outcome = if percent > 0.0 {
+        format!("You talked them down to **{}** (-{}%)! ❤", final_price, percent)
+    } else if percent < 0.0 {
+        format!("They talked you up to **{}** (+{}%)!", final_price, -percent)
+    } else {
+        format!("The price stays at **{}**.", final_price)
+    };
+
+    let haggle = format!(
+        "{} You rolled {} {:+} = {} against the shopkeeper's {} = {}. {}",
+        msg.author,
+        opposed.active,
+        opposed.active_bonus,
+        opposed.active_total(),
+        opposed.passive,
+        opposed.passive_total(),
+        outcome
+    );
+    let reply = msg.channel_id.say(&ctx.http, haggle).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+// Lists the dice for an embed field, falling back to a sparkline when there are too many to list
+fn pool_field(pool: &Pool) -> String {
+    let listed = pool.to_string();
+    if listed.chars().count() > MAX_FIELD_LENGTH {
+        pool.sparkline().chars().take(MAX_FIELD_LENGTH).collect()
+    } else {
+        listed
+    }
+}
+
+// Lists things for an embed field, cutting them off when there are too many to list
+fn list_field<T: std::fmt::Display>(items: &[T]) -> String {
+    let listed = items.iter().map(|item| item.to_string()).collect::<Vec<String>>().join(", ");
+    if listed.chars().count() > MAX_FIELD_LENGTH {
+        format!("{}…", listed.chars().take(MAX_FIELD_LENGTH - 1).collect::<String>())
+    } else {
+        listed
+    }
+}
+
+// Lists L5R dice symbols for an embed field, cutting them off when there are too many to list
+fn symbols_field(dice: &[l5r::L5rDie]) -> String {
+    let listed = l5r::symbols(dice);
+    if listed.chars().count() > MAX_FIELD_LENGTH {
+        format!("{}…", listed.chars().take(MAX_FIELD_LENGTH - 1).collect::<String>())
+    } else {
+        listed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice::pool::MAX_DICE;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn pool(dice: u32, sides: u32, target: u32) -> SuccessPool {
+        SuccessPool { dice, sides, target }
+    }
+
+    #[test]
+    fn ln_binomial_matches_small_coefficients() {
+        assert!((ln_binomial(5, 2).exp() - 10.0).abs() < EPSILON);
+        assert!((ln_binomial(10, 0).exp() - 1.0).abs() < EPSILON);
+        assert!((ln_binomial(10, 10).exp() - 1.0).abs() < EPSILON);
+        assert!((ln_binomial(52, 5).exp() - 2_598_960.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn chance_of_at_least_small_pools() {
+        assert!((pool(1, 6, 6).chance_of_at_least(1) - 1.0 / 6.0).abs() < EPSILON);
+        assert!((pool(2, 6, 4).chance_of_at_least(1) - 0.75).abs() < EPSILON);
+        assert!((pool(2, 6, 4).chance_of_at_least(2) - 0.25).abs() < EPSILON);
+        assert!((pool(3, 10, 8).chance_of_at_least(0) - 1.0).abs() < EPSILON);
+        assert_eq!(pool(3, 10, 8).chance_of_at_least(4), 0.0);
+    }
+
+    #[test]
+    fn chance_of_at_least_certain_success() {
+        assert_eq!(pool(5, 6, 1).chance_of_at_least(5), 1.0);
+    }
+
+    #[test]
+    fn chance_of_at_least_stays_finite_for_the_largest_pools() {
+        let big = pool(MAX_DICE, 10, 8);
+        for needed in [0, 1, 300, 500, MAX_DICE] {
+            let chance = big.chance_of_at_least(needed);
+            assert!(chance.is_finite() && (0.0..=1.0).contains(&chance), "{} successes gave {}", needed, chance);
+        }
+        assert!((big.chance_of_at_least(0) - 1.0).abs() < 1e-6);
+        assert!(big.chance_of_at_least(250) > 0.99);
+        assert!(big.chance_of_at_least(350) < 0.01);
+    }
+
+    #[test]
+    fn expected_successes() {
+        assert!((pool(10, 10, 8).expected_successes() - 3.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn parse_chance_reads_pool_and_needed() {
+        let (pools, needed) = parse_chance("5d10t8 >= 2", 100).unwrap();
+        assert_eq!((pools[0].dice, pools[0].sides, pools[0].target, needed), (5, 10, 8, 2));
+
+        let (_, needed) = parse_chance("5d10t8", 100).unwrap();
+        assert_eq!(needed, 1);
+    }
+
+    #[test]
+    fn parse_chance_rejects_bad_pools() {
+        assert!(parse_chance("0d10t8", 100).is_err());
+        assert!(parse_chance("101d10t8", 100).is_err());
+        assert!(parse_chance("5d10t11", 100).is_err());
+        assert!(parse_chance("5d10", 100).is_err());
+        assert!(parse_chance("60d10t8 + 41d6t4", 100).is_err());
+        assert!(parse_chance("5d10t8 + ", 100).is_err());
+    }
+
+    #[test]
+    fn parse_chance_reads_mixed_pools() {
+        let (pools, needed) = parse_chance("4d10t7 + 3d6t5 >= 3", 100).unwrap();
+        assert_eq!(pools.len(), 2);
+        assert_eq!((pools[1].dice, pools[1].sides, pools[1].target, needed), (3, 6, 5, 3));
+    }
+
+    #[test]
+    fn pools_with_the_same_odds_are_exact() {
+        // A d6 succeeding on 4+ and a d10 on 6+ both succeed half the time
+        let chance = exact_chance(&[pool(1, 6, 4), pool(1, 10, 6)], 2).unwrap();
+        assert!((chance - 0.25).abs() < EPSILON);
+
+        assert!(exact_chance(&[pool(1, 6, 4), pool(1, 6, 5)], 1).is_none());
+    }
+
+    #[test]
+    fn mixed_pools_are_simulated_close_to_the_real_chance() {
+        // Fails only if the d6 misses (1/2) and the d4 misses (3/4), so 5/8 succeed
+        let chance = simulated_chance(&[pool(1, 6, 4), pool(1, 4, 4)], 1);
+        assert!((chance - 0.625).abs() < 0.03, "simulated {}", chance);
+
+        assert_eq!(simulated_chance(&[pool(1, 6, 1), pool(1, 4, 1)], 2), 1.0);
+        assert_eq!(simulated_chance(&[pool(1, 6, 4), pool(1, 4, 4)], 3), 0.0);
+    }
+
+    #[test]
+    fn parse_coc_dice_counts_letters_or_digits() {
+        assert_eq!(parse_coc_dice("b"), Some(1));
+        assert_eq!(parse_coc_dice("bb"), Some(2));
+        assert_eq!(parse_coc_dice("b2"), Some(2));
+        assert_eq!(parse_coc_dice("pp"), Some(-2));
+        assert_eq!(parse_coc_dice("p1"), Some(-1));
+    }
+
+    #[test]
+    fn parse_coc_dice_rejects_everything_else() {
+        for option in ["", "x", "bp", "b0", "b-3", "p+1", "bx", "b99999999999"] {
+            assert_eq!(parse_coc_dice(option), None, "{}", option);
+        }
+    }
+
+    #[test]
+    fn parse_weighted_defaults_to_weight_one() {
+        let options = parse_weighted("sword:3 axe bow:2").unwrap();
+        assert_eq!(options, vec![("sword".to_string(), 3), ("axe".to_string(), 1), ("bow".to_string(), 2)]);
+
+        // Only the last colon splits off the weight
+        assert_eq!(parse_weighted("a:b:4").unwrap(), vec![("a:b".to_string(), 4)]);
+    }
+
+    #[test]
+    fn parse_weighted_rejects_bad_options() {
+        assert!(parse_weighted("").is_err());
+        assert!(parse_weighted(":3").is_err());
+        assert!(parse_weighted("sword:0").is_err());
+        assert!(parse_weighted("sword:lots").is_err());
+    }
+
+    #[test]
+    fn pick_reply_falls_back_to_the_picked_odds() {
+        let author = User::default();
+        let few = parse_weighted("sword:3 axe:1").unwrap();
+        assert!(pick_reply(&author, &few, 1).ends_with("I picked **axe**! (sword 75.00%, axe 25.00%)"));
+
+        let many = (0..300).map(|i| (format!("option{}", i), 1)).collect::<Vec<(String, u32)>>();
+        let reply = pick_reply(&author, &many, 7);
+        assert!(reply.ends_with("I picked **option7**! (0.33% chance, out of 300 options)"), "{}", reply);
+
+        let long = vec![("x".repeat(3000), 1)];
+        assert_eq!(pick_reply(&author, &long, 0).chars().count(), MAX_MESSAGE_LENGTH);
+    }
+}
@@ -0,0 +1,229 @@
+use serenity::{
+    framework::{
+        standard::{
+            Args,
+            CommandResult,
+            macros::{
+                command,
+            },
+        },
+    },
+    model::{
+        channel::Message,
+        id::{
+            ChannelId,
+            RoleId,
+        },
+    },
+    prelude::*,
+};
+
+use crate::settings::FunsiesSettings;
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_GUILD")]
+#[aliases("funconfig")]
+#[description = "Changes where the funsies commands work in this server.\n\n
+`!funsiesconfig off` turns them off and `!funsiesconfig on` turns them back on. `!funsiesconfig only #channel #another` keeps them to those channels, and `!funsiesconfig anywhere` lets them work everywhere again. Without anything after it, I'll tell you the current settings."]
+async fn funsiesconfig(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let mode = args.single::<String>().unwrap_or_default().to_lowercase();
+
+    let channels = match args.iter::<ChannelId>().collect::<Result<Vec<ChannelId>, _>>() {
+        Ok(channels) => channels,
+        Err(why) => {
+            let chan_error = format!("☢ That's not a channel I recognize! ☢\n Error parsing channel id: {}", why);
+            msg.channel_id.say(&ctx.http, chan_error).await?;
+            return Ok(());
+        }
+    };
+
+    let settings_data = ctx.data.read().await;
+    let mut registry = settings_data
+        .get::<crate::SettingsKey>()
+        .expect("Failed to retrieve guild settings!")
+        .lock().await;
+
+    let change: fn(&mut FunsiesSettings, &[ChannelId]) = match mode.as_str() {
+        "" => {
+            let status = describe(&registry.get(guild_id).funsies);
+            msg.channel_id.say(&ctx.http, status).await?;
+            return Ok(());
+        },
+        "off" => |funsies, _| funsies.disabled = true,
+        "on" => |funsies, _| funsies.disabled = false,
+        "anywhere" => |funsies, _| funsies.channels.clear(),
+        "only" if !channels.is_empty() => |funsies, channels| {
+            funsies.channels = channels.iter().map(|channel| channel.0).collect();
+        },
+        "only" => {
+            let chan_error = "☢ Which channels? ☢\nGive me some channel mentions, like `!funsiesconfig only #bot-spam`.".to_string();
+            msg.channel_id.say(&ctx.http, chan_error).await?;
+            return Ok(());
+        },
+        _ => {
+            let mode_error = format!("☢ I don't know how to do `{}`! ☢\nTry `on`, `off`, `only` or `anywhere`.", mode);
+            msg.channel_id.say(&ctx.http, mode_error).await?;
+            return Ok(());
+        },
+    };
+
+    if let Err(why) = registry.update(guild_id, |settings| change(&mut settings.funsies, &channels)).await {
+        let save_error = format!("☢ Something went wrong! ☢\n Error saving settings: {}", why);
+        msg.channel_id.say(&ctx.http, save_error).await?;
+        return Ok(());
+    }
+
+    let status = describe(&registry.get(guild_id).funsies);
+    msg.channel_id.say(&ctx.http, format!("Okay! ❤ {}", status)).await?;
+
+    Ok(())
+}
+
+fn describe(funsies: &FunsiesSettings) -> String {
+    let channels = funsies.channels
+        .iter()
+        .map(|channel| ChannelId(*channel).mention().to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    match (funsies.disabled, channels.is_empty()) {
+        (true, _) => "Funsies are turned off in this server.".to_string(),
+        (false, true) => "Funsies work in every channel in this server.".to_string(),
+        (false, false) => format!("Funsies only work in {}.", channels),
+    }
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_GUILD")]
+#[description = "Sets the GM role for this server.\n\n
+Everyone with the GM role gets a DM with the result of every !gmroll. Give me a role mention, like `!gmrole @GM`, or `!gmrole none` to stop sending them. Without anything after it, I'll tell you the current GM role.\n
+I need the Server Members Intent to find who has the role."]
+async fn gmrole(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let input = args.rest().trim().to_lowercase();
+    let gm_role = match input.as_str() {
+        "" => {
+            let settings_data = ctx.data.read().await;
+            let registry = settings_data
+                .get::<crate::SettingsKey>()
+                .expect("Failed to retrieve guild settings!")
+                .lock().await;
+
+            let status = match registry.get(guild_id).gm_role {
+                Some(role) => format!("The GM role is {}.", RoleId(role).mention()),
+                None => "This server doesn't have a GM role.".to_string(),
+            };
+            msg.channel_id.say(&ctx.http, status).await?;
+            return Ok(());
+        },
+        "none" => None,
+        _ => match input.parse::<RoleId>() {
+            Ok(role) => Some(role),
+            Err(why) => {
+                let role_error = format!("☢ That's not a role I recognize! ☢\n Error parsing role id: {}", why);
+                msg.channel_id.say(&ctx.http, role_error).await?;
+                return Ok(());
+            }
+        },
+    };
+
+    let settings_data = ctx.data.read().await;
+    let mut registry = settings_data
+        .get::<crate::SettingsKey>()
+        .expect("Failed to retrieve guild settings!")
+        .lock().await;
+
+    if let Err(why) = registry.update(guild_id, |settings| settings.gm_role = gm_role.map(|role| role.0)).await {
+        let save_error = format!("☢ Something went wrong! ☢\n Error saving settings: {}", why);
+        msg.channel_id.say(&ctx.http, save_error).await?;
+        return Ok(());
+    }
+
+    let confirm = match gm_role {
+        Some(role) => format!("Okay! ❤ {} will get a copy of every secret roll.", role.mention()),
+        None => "Okay! ❤ Secret rolls will only go to the person rolling.".to_string(),
+    };
+    msg.channel_id.say(&ctx.http, confirm).await?;
+
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+#[required_permissions("MANAGE_GUILD")]
+#[description = "Turns command usage counting on or off for this server.\n\n
+`!usagetracking off` stops counting and forgets the counts so far, and `!usagetracking on` starts again. Without anything after it, I'll tell you whether I'm counting. See !usage for what gets counted."]
+async fn usagetracking(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let opt_out = match args.rest().trim().to_lowercase().as_str() {
+        "" => None,
+        "on" => Some(false),
+        "off" => Some(true),
+        other => {
+            let mode_error = format!("☢ I don't know how to do `{}`! ☢\nTry `on` or `off`.", other);
+            msg.channel_id.say(&ctx.http, mode_error).await?;
+            return Ok(());
+        },
+    };
+
+    let settings_data = ctx.data.read().await;
+    let mut registry = settings_data
+        .get::<crate::SettingsKey>()
+        .expect("Failed to retrieve guild settings!")
+        .lock().await;
+
+    let opt_out = match opt_out {
+        Some(opt_out) => opt_out,
+        None => {
+            let status = if registry.get(guild_id).usage_opt_out {
+                "I'm not counting command usage in this server."
+            } else {
+                "I'm counting command usage in this server."
+            };
+            msg.channel_id.say(&ctx.http, status).await?;
+            return Ok(());
+        },
+    };
+
+    if let Err(why) = registry.update(guild_id, |settings| settings.usage_opt_out = opt_out).await {
+        let save_error = format!("☢ Something went wrong! ☢\n Error saving settings: {}", why);
+        msg.channel_id.say(&ctx.http, save_error).await?;
+        return Ok(());
+    }
+
+    if opt_out {
+        let mut usage = settings_data
+            .get::<crate::UsageKey>()
+            .expect("Failed to retrieve usage registry!")
+            .lock().await;
+        if let Err(why) = usage.clear(guild_id).await {
+            let clear_error = format!("☢ Something went wrong! ☢\n Error forgetting usage: {}", why);
+            msg.channel_id.say(&ctx.http, clear_error).await?;
+            return Ok(());
+        }
+    }
+
+    let confirm = if opt_out {
+        "Okay! ❤ I stopped counting commands here and forgot the counts so far."
+    } else {
+        "Okay! ❤ I'll count which commands get used here."
+    };
+    msg.channel_id.say(&ctx.http, confirm).await?;
+
+    Ok(())
+}
@@ -1,21 +1,276 @@
 use serde::{Deserialize, Serialize};
-use serde_json;
-use std::fs;
+
+use crate::dice::pool::{
+    MAX_DICE,
+    Pool,
+};
+use std::{
+    fmt,
+    fs,
+};
+
+const CONFIG_PATH: &str = "config.toml";
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Config {
     pub discord_token: String,
+    #[serde(default = "default_prefix")]
     pub prefix: String,
-    pub log_folder_path: String,
     pub pfp_source: String,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub cleanup: CleanupConfig,
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    #[serde(default)]
+    pub features: FeaturesConfig,
+    #[serde(default)]
+    pub haggle: HaggleConfig,
+    #[serde(default)]
+    pub macros: MacrosConfig,
+    #[serde(default)]
+    pub aliases: AliasesConfig,
+    #[serde(default)]
+    pub settings: SettingsConfig,
+    #[serde(default)]
+    pub mtg: MtgConfig,
+    #[serde(default)]
+    pub usage: UsageConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct LoggingConfig {
+    pub folder_path: String,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> LoggingConfig {
+        LoggingConfig { folder_path: "./Logs".to_string() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CleanupConfig {
+    pub delete_emoji: String,
+    pub delete_window_secs: u64,
+    // 0 means funsies replies are never cleaned up
+    pub funsies_ttl_secs: u64,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> CleanupConfig {
+        CleanupConfig {
+            delete_emoji: "❌".to_string(),
+            delete_window_secs: 300,
+            funsies_ttl_secs: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct LimitsConfig {
+    pub max_chance_dice: u32,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> LimitsConfig {
+        LimitsConfig { max_chance_dice: 100 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct FeaturesConfig {
+    pub rolling: bool,
+    pub macros: bool,
+    pub aliases: bool,
+    pub mtg: bool,
+    pub logging: bool,
+    pub funsies: bool,
+}
+
+impl Default for FeaturesConfig {
+    fn default() -> FeaturesConfig {
+        FeaturesConfig {
+            rolling: true,
+            macros: true,
+            aliases: true,
+            mtg: true,
+            logging: true,
+            funsies: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct HaggleConfig {
+    // Dice rolled by both the customer and the shopkeeper
+    pub dice: String,
+    // How much the price moves for every point the opposed roll is won or lost by
+    pub percent_per_point: f64,
+    pub max_percent: f64,
+}
+
+impl Default for HaggleConfig {
+    fn default() -> HaggleConfig {
+        HaggleConfig {
+            dice: "1d20".to_string(),
+            percent_per_point: 2.0,
+            max_percent: 50.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct MacrosConfig {
+    pub file_path: String,
+    pub max_per_user: usize,
+}
+
+impl Default for MacrosConfig {
+    fn default() -> MacrosConfig {
+        MacrosConfig {
+            file_path: "./macros.json".to_string(),
+            max_per_user: 25,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AliasesConfig {
+    pub file_path: String,
+    pub max_per_guild: usize,
+}
+
+impl Default for AliasesConfig {
+    fn default() -> AliasesConfig {
+        AliasesConfig {
+            file_path: "./aliases.json".to_string(),
+            max_per_guild: 50,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct SettingsConfig {
+    pub file_path: String,
+}
+
+impl Default for SettingsConfig {
+    fn default() -> SettingsConfig {
+        SettingsConfig { file_path: "./guild_settings.json".to_string() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct MtgConfig {
+    pub file_path: String,
+}
+
+impl Default for MtgConfig {
+    fn default() -> MtgConfig {
+        MtgConfig { file_path: "./mtg_tables.json".to_string() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct UsageConfig {
+    pub file_path: String,
+    // Counts are saved this often rather than after every command
+    pub flush_interval_secs: u64,
+}
+
+impl Default for UsageConfig {
+    fn default() -> UsageConfig {
+        UsageConfig { file_path: "./usage.json".to_string(), flush_interval_secs: 60 }
+    }
+}
+
+fn default_prefix() -> String {
+    "!".to_string()
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read(std::io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Read(why) => write!(f, "could not read {}: {}", CONFIG_PATH, why),
+            ConfigError::Parse(why) => write!(f, "could not parse {}: {}", CONFIG_PATH, why),
+            ConfigError::Invalid(why) => write!(f, "invalid value in {}: {}", CONFIG_PATH, why),
+        }
+    }
 }
 
 impl Config {
-    pub fn new() -> Config {
-        let data = fs::read_to_string("config.json").expect("Failed to find config.json file");
+    pub fn new() -> Result<Config, ConfigError> {
+        let data = fs::read_to_string(CONFIG_PATH).map_err(ConfigError::Read)?;
 
-        let config = serde_json::from_str(&data).expect("Error parsing config data");
+        let config: Config = toml::from_str(&data).map_err(ConfigError::Parse)?;
+        config.validate()?;
 
-        config
+        Ok(config)
     }
-}
\ No newline at end of file
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.discord_token.trim().is_empty() {
+            return Err(ConfigError::Invalid("`discord_token` must not be empty".to_string()));
+        }
+        if self.prefix.is_empty() || self.prefix.contains(char::is_whitespace) {
+            return Err(ConfigError::Invalid("`prefix` must be non-empty and contain no whitespace".to_string()));
+        }
+        if self.logging.folder_path.trim().is_empty() {
+            return Err(ConfigError::Invalid("`logging.folder_path` must not be empty".to_string()));
+        }
+        if self.cleanup.delete_emoji.is_empty() {
+            return Err(ConfigError::Invalid("`cleanup.delete_emoji` must not be empty".to_string()));
+        }
+        if self.cleanup.delete_window_secs == 0 {
+            return Err(ConfigError::Invalid("`cleanup.delete_window_secs` must be at least 1".to_string()));
+        }
+        if !(1..=MAX_DICE).contains(&self.limits.max_chance_dice) {
+            return Err(ConfigError::Invalid(format!("`limits.max_chance_dice` must be between 1 and {}", MAX_DICE)));
+        }
+        if self.macros.file_path.trim().is_empty() {
+            return Err(ConfigError::Invalid("`macros.file_path` must not be empty".to_string()));
+        }
+        if self.aliases.file_path.trim().is_empty() {
+            return Err(ConfigError::Invalid("`aliases.file_path` must not be empty".to_string()));
+        }
+        if self.settings.file_path.trim().is_empty() {
+            return Err(ConfigError::Invalid("`settings.file_path` must not be empty".to_string()));
+        }
+        if self.mtg.file_path.trim().is_empty() {
+            return Err(ConfigError::Invalid("`mtg.file_path` must not be empty".to_string()));
+        }
+        if self.usage.file_path.trim().is_empty() {
+            return Err(ConfigError::Invalid("`usage.file_path` must not be empty".to_string()));
+        }
+        if self.usage.flush_interval_secs == 0 {
+            return Err(ConfigError::Invalid("`usage.flush_interval_secs` must be at least 1".to_string()));
+        }
+        if let Err(why) = self.haggle.dice.parse::<Pool>() {
+            return Err(ConfigError::Invalid(format!("`haggle.dice` is not valid dice: {}", why)));
+        }
+        if self.haggle.percent_per_point < 0.0 || !(0.0..=100.0).contains(&self.haggle.max_percent) {
+            return Err(ConfigError::Invalid("`haggle.percent_per_point` must not be negative and `haggle.max_percent` must be between 0 and 100".to_string()));
+        }
+
+        Ok(())
+    }
+}
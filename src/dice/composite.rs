@@ -0,0 +1,128 @@
+use std::fmt;
+
+use super::{
+    die::Die,
+    pool::{
+        MAX_DICE,
+        Pool,
+    },
+    RollError,
+};
+
+const DIGIT_SIDES: u32 = 6;
+
+// A d66-style roll: a few d6s read as the digits of one number, so a 3 and a 5 make 35
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompositeRoll {
+    pub digits: Pool,
+}
+
+impl CompositeRoll {
+    pub fn new(digits: u32) -> CompositeRoll {
+        CompositeRoll { digits: Pool::rolled(digits, DIGIT_SIDES) }
+    }
+
+    pub fn value(&self) -> u32 {
+        self.digits.dice().iter().fold(0, |value, die| value * 10 + die.result)
+    }
+}
+
+impl fmt::Display for CompositeRoll {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.digits.dice().iter().map(|die| die.to_string()).collect::<Vec<String>>();
+        write!(f, "{} ({})", self.value(), digits.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CompositePool {
+    rolls: Vec<CompositeRoll>,
+}
+
+impl CompositePool {
+    pub fn total(&self) -> u32 {
+        self.rolls.iter().map(CompositeRoll::value).sum()
+    }
+
+    pub fn dice_count(&self) -> u64 {
+        self.rolls.iter().map(|roll| roll.digits.dice().len() as u64).sum()
+    }
+
+    pub fn dice(&self) -> impl Iterator<Item = &Die> {
+        self.rolls.iter().flat_map(|roll| roll.digits.dice())
+    }
+}
+
+impl fmt::Display for CompositePool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rolls = self.rolls.iter().map(|roll| roll.to_string()).collect::<Vec<String>>();
+        write!(f, "[{}]", rolls.join(", "))
+    }
+}
+
+// Parses `d66`, `d666` or several of them like `2d66`. Returns None for anything else, so `d6` and `d20` stay
+// ordinary dice. Keep, drop, sort and target modifiers don't work on digits, so `2d66k1` is an error rather than
+// two 66-sided dice.
+pub fn parse_composite(notation: &str) -> Option<Result<CompositePool, RollError>> {
+    let (count_str, sides_str) = notation.split_once('d')?;
+    let digits_end = sides_str.find(|c: char| !c.is_ascii_digit()).unwrap_or(sides_str.len());
+    let (digits_str, modifier) = sides_str.split_at(digits_end);
+    if !(2..=3).contains(&digits_str.len()) || !digits_str.chars().all(|c| c == '6') {
+        return None;
+    }
+    if !modifier.is_empty() {
+        return Some(Err(RollError::Syntax(format!("`{}` can't be added to d{} dice", modifier, digits_str))));
+    }
+
+    let count = if count_str.is_empty() { Ok(1) } else {
+        count_str
+            .parse::<u32>()
+            .map_err(|why| RollError::Syntax(format!("invalid number of dice `{}`: {}", count_str, why)))
+    };
+
+    Some(count.and_then(|count| {
+        if count == 0 {
+            return Err(RollError::Syntax("can't roll 0 dice".to_string()));
+        }
+
+        let total_dice = u64::from(count) * digits_str.len() as u64;
+        if total_dice > u64::from(MAX_DICE) {
+            return Err(RollError::TooManyDice(total_dice));
+        }
+
+        let rolls = (0..count).map(|_| CompositeRoll::new(digits_str.len() as u32)).collect();
+        Ok(CompositePool { rolls })
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_dice_as_digits() {
+        let roll = CompositeRoll { digits: Pool::from_results(DIGIT_SIDES, &[3, 5]) };
+        assert_eq!(roll.value(), 35);
+        assert_eq!(roll.to_string(), "35 (3, 5)");
+
+        let roll = CompositeRoll { digits: Pool::from_results(DIGIT_SIDES, &[6, 1, 2]) };
+        assert_eq!(roll.value(), 612);
+    }
+
+    #[test]
+    fn leaves_other_dice_alone() {
+        for notation in ["d6", "d20", "d60", "d6666", "66"] {
+            assert!(parse_composite(notation).is_none(), "{}", notation);
+        }
+    }
+
+    #[test]
+    fn checks_the_count_and_modifiers() {
+        assert_eq!(parse_composite("d66").unwrap().unwrap().dice_count(), 2);
+        assert_eq!(parse_composite("3d666").unwrap().unwrap().dice_count(), 9);
+
+        for notation in ["0d66", "xd66", "501d66", "2d66k1", "d66s", "d666t3"] {
+            assert!(parse_composite(notation).unwrap().is_err(), "{}", notation);
+        }
+    }
+}
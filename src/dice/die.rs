@@ -0,0 +1,105 @@
+use rand::Rng;
+use std::fmt;
+
+use super::RollError;
+
+// Enough for any reroll rule, without letting a die that keeps getting rerolled grow forever
+pub const MAX_HISTORY: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Die {
+    pub sides: u32,
+    pub result: u32,
+    // What the die showed before each reroll, oldest first. Only the last MAX_HISTORY are kept.
+    pub previous: Vec<u32>,
+}
+
+impl Die {
+    pub fn new(sides: u32) -> Result<Die, RollError> {
+        if sides == 0 {
+            return Err(RollError::Syntax("dice need at least one side".to_string()));
+        }
+
+        Ok(Die::rolled(sides))
+    }
+
+    // A fresh die the same size as this one, like the extra die from an explosion
+    pub fn another(&self) -> Die {
+        Die::rolled(self.sides)
+    }
+
+    // Only for sizes that are already known to be good, like the dice a game system always rolls. Anything from
+    // user input goes through Die::new.
+    pub(super) fn rolled(sides: u32) -> Die {
+        let mut die = Die { sides, result: 0, previous: Vec::new() };
+        die.roll();
+
+        die
+    }
+
+    pub fn roll(&mut self) {
+        self.result = rand::thread_rng().gen_range(1..=self.sides);
+    }
+
+    // Rolls the die again, remembering what it showed before so the reroll can be shown alongside it
+    pub fn reroll(&mut self) {
+        if self.previous.len() == MAX_HISTORY {
+            self.previous.remove(0);
+        }
+        self.previous.push(self.result);
+        self.roll();
+    }
+
+    // Rolls a die with custom faces, like a planar die, and returns the face that came up
+    pub fn roll_face<T: Copy>(faces: &[T]) -> Result<T, RollError> {
+        if faces.is_empty() {
+            return Err(RollError::Syntax("a die needs at least one face".to_string()));
+        }
+        let die = Die::rolled(faces.len() as u32);
+
+        Ok(faces[die.result as usize - 1])
+    }
+}
+
+impl fmt::Display for Die {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for previous in &self.previous {
+            write!(f, "{} → ", previous)?;
+        }
+        write!(f, "{}", self.result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_sides_and_no_faces_are_errors() {
+        assert!(Die::new(0).is_err());
+        assert!(Die::roll_face::<u32>(&[]).is_err());
+        assert_eq!(Die::roll_face(&["only"]), Ok("only"));
+    }
+
+    #[test]
+    fn rerolls_keep_every_earlier_result_in_order() {
+        let mut die = Die::new(1).unwrap();
+        die.result = 4;
+        die.reroll();
+        die.result = 2;
+        die.reroll();
+
+        assert_eq!(die.previous, vec![4, 2]);
+        assert_eq!(die.to_string(), "4 → 2 → 1");
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut die = Die::new(6).unwrap();
+        for _ in 0..MAX_HISTORY + 5 {
+            die.reroll();
+        }
+
+        assert_eq!(die.previous.len(), MAX_HISTORY);
+    }
+}
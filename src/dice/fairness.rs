@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use super::{
+    die::Die,
+    roll::Roll,
+};
+
+pub const MAX_FAIRNESS_SIDES: u32 = 100;
+const ROLLS_PER_FACE: u32 = 1000;
+// The chi-squared test isn't reliable until every face is expected to come up at least this often
+const MIN_ROLLS_PER_FACE: u64 = 5;
+
+// A chi-squared goodness-of-fit test of how evenly a die's faces come up over a lot of rolls
+#[derive(Debug, Clone)]
+pub struct FairnessReport {
+    pub sides: u32,
+    pub counts: Vec<u64>,
+    pub chi_squared: f64,
+    pub critical_value: f64,
+}
+
+impl FairnessReport {
+    // Rolls a fresh batch just for the test, for when there aren't enough real rolls of a die to go on
+    pub fn sample(sides: u32) -> FairnessReport {
+        let rolls = sides * ROLLS_PER_FACE;
+        let mut counts = vec![0; sides as usize];
+        for _ in 0..rolls {
+            counts[Die::rolled(sides).result as usize - 1] += 1;
+        }
+
+        FairnessReport::from_counts(sides, counts)
+    }
+
+    // How many times each face came up, lowest face first
+    pub fn from_counts(sides: u32, counts: Vec<u64>) -> FairnessReport {
+        let expected = counts.iter().sum::<u64>() as f64 / f64::from(sides);
+        let chi_squared = counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum();
+
+        FairnessReport { sides, counts, chi_squared, critical_value: critical_value(sides - 1) }
+    }
+
+    pub fn rolls(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    // Even a fair die fails this about one time in twenty
+    pub fn looks_fair(&self) -> bool {
+        self.chi_squared <= self.critical_value
+    }
+}
+
+// How often each face has come up on every die size, counting the dice people have actually rolled since the bot
+// started. Fudge dice aren't numbered, so they're left out.
+#[derive(Debug, Default)]
+pub struct FaceTally {
+    counts: HashMap<u32, Vec<u64>>,
+}
+
+impl FaceTally {
+    pub fn record(&mut self, roll: &Roll) {
+        for die in roll.dice() {
+            if !(2..=MAX_FAIRNESS_SIDES).contains(&die.sides) { continue; }
+
+            let counts = self.counts.entry(die.sides).or_insert_with(|| vec![0; die.sides as usize]);
+            // Faces a die showed before it was rerolled were real rolls too
+            for &face in die.previous.iter().chain(std::iter::once(&die.result)) {
+                counts[face as usize - 1] += 1;
+            }
+        }
+    }
+
+    pub fn rolls(&self, sides: u32) -> u64 {
+        self.counts.get(&sides).map(|counts| counts.iter().sum()).unwrap_or(0)
+    }
+
+    // None until there have been enough rolls of the die for the test to mean anything
+    pub fn report(&self, sides: u32) -> Option<FairnessReport> {
+        if self.rolls(sides) < MIN_ROLLS_PER_FACE * u64::from(sides) { return None; }
+
+        Some(FairnessReport::from_counts(sides, self.counts.get(&sides)?.clone()))
+    }
+}
+
+// The chi-squared value a fair die stays under 95% of the time, using the Wilson-Hilferty approximation
+fn critical_value(degrees_of_freedom: u32) -> f64 {
+    const Z_95: f64 = 1.644_854;
+    let k = f64::from(degrees_of_freedom);
+    let spread = 2.0 / (9.0 * k);
+
+    k * (1.0 - spread + Z_95 * spread.sqrt()).powi(3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn critical_values_match_the_tables() {
+        // Published 95% points for 5, 19 and 99 degrees of freedom
+        assert!((critical_value(5) - 11.070).abs() < 0.05);
+        assert!((critical_value(19) - 30.144).abs() < 0.05);
+        assert!((critical_value(99) - 123.225).abs() < 0.05);
+    }
+
+    #[test]
+    fn even_counts_are_fair_and_lopsided_ones_are_not() {
+        let even = FairnessReport::from_counts(4, vec![25, 25, 25, 25]);
+        assert_eq!(even.chi_squared, 0.0);
+        assert!(even.looks_fair());
+        assert_eq!(even.rolls(), 100);
+
+        assert!(!FairnessReport::from_counts(4, vec![70, 10, 10, 10]).looks_fair());
+    }
+
+    #[test]
+    fn tally_counts_every_face_shown() {
+        let mut tally = FaceTally::default();
+        tally.record(&"2d1 + 1d20 + 4dF + 5".parse::<Roll>().unwrap());
+        tally.record(&"d66".parse::<Roll>().unwrap());
+
+        // d1s can't be tested and Fudge dice aren't numbered, so only the d20 and the d66's two d6s count
+        assert_eq!(tally.rolls(1), 0);
+        assert_eq!(tally.rolls(20), 1);
+        assert_eq!(tally.rolls(6), 2);
+        assert_eq!(tally.rolls(3), 0);
+    }
+
+    #[test]
+    fn tally_needs_enough_rolls_for_a_report() {
+        let mut tally = FaceTally::default();
+        let roll = "9d2".parse::<Roll>().unwrap();
+        tally.record(&roll);
+        assert!(tally.report(2).is_none());
+
+        tally.record(&roll);
+        let report = tally.report(2).unwrap();
+        assert_eq!(report.rolls(), 18);
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+
+use super::{
+    die::Die,
+    pool::MAX_DICE,
+    RollError,
+};
+
+const FUDGE_FACES: [i8; 6] = [-1, -1, 0, 0, 1, 1];
+
+// A Fudge/FATE die: two faces each of -1, 0 and +1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FudgeDie {
+    pub face: i8,
+}
+
+impl FudgeDie {
+    pub fn new() -> FudgeDie {
+        let die = Die::rolled(FUDGE_FACES.len() as u32);
+
+        FudgeDie { face: FUDGE_FACES[die.result as usize - 1] }
+    }
+}
+
+impl fmt::Display for FudgeDie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.face {
+            1 => write!(f, "+"),
+            -1 => write!(f, "-"),
+            _ => write!(f, "0"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FudgePool {
+    dice: Vec<FudgeDie>,
+}
+
+impl FudgePool {
+    pub fn new(count: u32) -> FudgePool {
+        FudgePool { dice: (0..count).map(|_| FudgeDie::new()).collect() }
+    }
+
+    pub fn dice(&self) -> &[FudgeDie] {
+        &self.dice
+    }
+
+    pub fn total(&self) -> i64 {
+        self.dice.iter().map(|die| i64::from(die.face)).sum()
+    }
+}
+
+impl fmt::Display for FudgePool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let faces = self.dice.iter().map(|die| die.to_string()).collect::<Vec<String>>();
+        write!(f, "[{}]", faces.join(", "))
+    }
+}
+
+// Parses `4df`, or `df` for a single die
+pub fn parse_fudge(notation: &str) -> Result<FudgePool, RollError> {
+    let count_str = notation
+        .strip_suffix("df")
+        .ok_or_else(|| RollError::Syntax(format!("`{}` isn't fudge dice, try something like `4df`", notation)))?;
+    let count = if count_str.is_empty() { 1 } else {
+        count_str
+            .parse::<u32>()
+            .map_err(|why| RollError::Syntax(format!("invalid number of fudge dice `{}`: {}", count_str, why)))?
+    };
+    if count == 0 {
+        return Err(RollError::Syntax("can't roll 0 dice".to_string()));
+    }
+    if count > MAX_DICE {
+        return Err(RollError::TooManyDice(u64::from(count)));
+    }
+
+    Ok(FudgePool::new(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_number_of_dice() {
+        assert_eq!(parse_fudge("df").unwrap().dice().len(), 1);
+        assert_eq!(parse_fudge("4df").unwrap().dice().len(), 4);
+
+        for notation in ["0df", "4dfdf", "xdf", "-1df", "4d"] {
+            assert!(parse_fudge(notation).is_err(), "{}", notation);
+        }
+        assert_eq!(parse_fudge(&format!("{}df", MAX_DICE + 1)), Err(RollError::TooManyDice(u64::from(MAX_DICE) + 1)));
+    }
+
+    #[test]
+    fn adds_up_the_faces() {
+        let pool = FudgePool { dice: [1, 1, 0, -1].iter().map(|&face| FudgeDie { face }).collect() };
+
+        assert_eq!(pool.total(), 1);
+        assert_eq!(pool.to_string(), "[+, +, 0, -]");
+        assert!(FudgePool::new(50).dice().iter().all(|die| (-1..=1).contains(&die.face)));
+    }
+}
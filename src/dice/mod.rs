@@ -0,0 +1,28 @@
+use std::fmt;
+
+pub mod composite;
+pub mod die;
+pub mod fairness;
+pub mod fudge;
+pub mod opposed;
+pub mod pool;
+pub mod report;
+pub mod roll;
+pub mod systems;
+
+#[derive(Debug, PartialEq)]
+pub enum RollError {
+    Syntax(String),
+    TooManyDice(u64),
+}
+
+impl fmt::Display for RollError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RollError::Syntax(why) => write!(f, "{}", why),
+            RollError::TooManyDice(count) => write!(f, "{} dice is too many, I can only roll up to {} at once", count, pool::MAX_DICE),
+        }
+    }
+}
+
+impl std::error::Error for RollError {}
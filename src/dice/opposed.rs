@@ -0,0 +1,75 @@
+use super::{
+    pool::Pool,
+    RollError,
+};
+
+// Two sides roll the same dice and add their own bonuses, the higher total wins
+#[derive(Debug, Clone)]
+pub struct OpposedRoll {
+    pub active: Pool,
+    pub active_bonus: i64,
+    pub passive: Pool,
+    pub passive_bonus: i64,
+}
+
+impl OpposedRoll {
+    pub fn new(dice: &str, active_bonus: i64, passive_bonus: i64) -> Result<OpposedRoll, RollError> {
+        let active = dice.parse::<Pool>()?;
+        let passive = dice.parse::<Pool>()?;
+
+        Ok(OpposedRoll { active, active_bonus, passive, passive_bonus })
+    }
+
+    pub fn active_total(&self) -> i64 {
+        i64::from(self.active.total()).saturating_add(self.active_bonus)
+    }
+
+    pub fn passive_total(&self) -> i64 {
+        i64::from(self.passive.total()).saturating_add(self.passive_bonus)
+    }
+
+    // Positive when the active side wins. Bonuses come straight from the user, so the totals saturate instead of
+    // overflowing on something like `!haggle 250 9223372036854775807`.
+    pub fn margin(&self) -> i64 {
+        self.active_total().saturating_sub(self.passive_total())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opposed(active: &[u32], active_bonus: i64, passive: &[u32], passive_bonus: i64) -> OpposedRoll {
+        OpposedRoll {
+            active: Pool::from_results(6, active),
+            active_bonus,
+            passive: Pool::from_results(6, passive),
+            passive_bonus,
+        }
+    }
+
+    #[test]
+    fn margin_is_positive_when_the_active_side_wins() {
+        let roll = opposed(&[4, 5], 2, &[6, 3], 0);
+        assert_eq!((roll.active_total(), roll.passive_total()), (11, 9));
+        assert_eq!(roll.margin(), 2);
+
+        assert_eq!(opposed(&[1], -3, &[2], 1).margin(), -5);
+    }
+
+    #[test]
+    fn extreme_bonuses_saturate() {
+        assert_eq!(opposed(&[6], i64::MAX, &[1], 0).active_total(), i64::MAX);
+        assert_eq!(opposed(&[6], i64::MAX, &[1], i64::MIN).margin(), i64::MAX);
+        assert_eq!(opposed(&[1], i64::MIN, &[6], i64::MAX).margin(), i64::MIN);
+    }
+
+    #[test]
+    fn both_sides_roll_the_same_dice() {
+        let roll = OpposedRoll::new("3d6", 0, 0).unwrap();
+        assert_eq!(roll.active.dice().len(), 3);
+        assert_eq!(roll.passive.dice().len(), 3);
+
+        assert!(OpposedRoll::new("3d", 0, 0).is_err());
+    }
+}
@@ -0,0 +1,431 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    str::FromStr,
+};
+
+use super::{
+    die::Die,
+    RollError,
+};
+
+pub const MAX_DICE: u32 = 1000;
+pub const MAX_SIDES: u32 = 1_000_000;
+
+const SPARK_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Pool {
+    dice: Vec<Die>,
+}
+
+impl Pool {
+    pub fn new(count: u32, sides: u32) -> Result<Pool, RollError> {
+        let dice = (0..count).map(|_| Die::new(sides)).collect::<Result<Vec<Die>, RollError>>()?;
+
+        Ok(Pool { dice })
+    }
+
+    // Like Pool::new, for sizes that are already known to be good, like a game system's d10s
+    pub(super) fn rolled(count: u32, sides: u32) -> Pool {
+        Pool { dice: (0..count).map(|_| Die::rolled(sides)).collect() }
+    }
+
+    // Dice with known results, so tests don't depend on what gets rolled
+    #[cfg(test)]
+    pub fn from_results(sides: u32, results: &[u32]) -> Pool {
+        Pool { dice: results.iter().map(|&result| Die { sides, result, previous: Vec::new() }).collect() }
+    }
+
+    pub fn builder() -> PoolBuilder {
+        PoolBuilder::default()
+    }
+
+    pub fn dice(&self) -> &[Die] {
+        &self.dice
+    }
+
+    pub fn total(&self) -> u32 {
+        self.dice.iter().map(|die| die.result).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dice.is_empty()
+    }
+
+    pub fn count_at_least(&self, target: u32) -> u32 {
+        self.dice.iter().filter(|die| die.result >= target).count() as u32
+    }
+
+    pub fn count_exactly(&self, face: u32) -> u32 {
+        self.dice.iter().filter(|die| die.result == face).count() as u32
+    }
+
+    // Keeps the highest dice, leaving them in the order they were rolled
+    pub fn keep_highest(&self, count: u32) -> Pool {
+        let ranked = self.ranked();
+        let start = ranked.len().saturating_sub(count as usize);
+
+        self.select(&ranked[start..])
+    }
+
+    // Keeps the lowest dice, leaving them in the order they were rolled
+    pub fn keep_lowest(&self, count: u32) -> Pool {
+        let ranked = self.ranked();
+        let end = ranked.len().min(count as usize);
+
+        self.select(&ranked[..end])
+    }
+
+    // Drops the highest dice, leaving the rest in the order they were rolled
+    pub fn drop_highest(&self, count: u32) -> Pool {
+        self.keep_lowest((self.dice.len() as u32).saturating_sub(count))
+    }
+
+    // Drops the lowest dice, leaving the rest in the order they were rolled
+    pub fn drop_lowest(&self, count: u32) -> Pool {
+        self.keep_highest((self.dice.len() as u32).saturating_sub(count))
+    }
+
+    // Drops every die showing one of the faces
+    pub fn drop_exact(&self, faces: &[u32]) -> Pool {
+        Pool { dice: self.dice.iter().filter(|die| !faces.contains(&die.result)).cloned().collect() }
+    }
+
+    // Keeps only the dice showing one of the faces
+    pub fn keep_exact(&self, faces: &[u32]) -> Pool {
+        Pool { dice: self.dice.iter().filter(|die| faces.contains(&die.result)).cloned().collect() }
+    }
+
+    // The same dice sorted by result, for easier reading. Totals don't change.
+    pub fn sorted(&self, descending: bool) -> Pool {
+        let mut dice = self.dice.clone();
+        dice.sort_by_key(|die| die.result);
+        if descending { dice.reverse(); }
+
+        Pool { dice }
+    }
+
+    // Indices of the dice from lowest to highest result. Ties stay in roll order, so keeping the highest N and
+    // the lowest (len - N) always splits the pool cleanly in two.
+    fn ranked(&self) -> Vec<usize> {
+        let mut indices = (0..self.dice.len()).collect::<Vec<usize>>();
+        indices.sort_by_key(|&i| self.dice[i].result);
+
+        indices
+    }
+
+    fn select(&self, indices: &[usize]) -> Pool {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+
+        Pool { dice: indices.iter().map(|&i| self.dice[i].clone()).collect() }
+    }
+
+    // Every die at or above the threshold adds a new die of the same size, which can explode again in turn.
+    // Returns only the new dice, capped at MAX_DICE so a threshold of 1 can't explode forever.
+    pub fn explode(&self, threshold: u32) -> Pool {
+        let mut pending = self.dice
+            .iter()
+            .filter(|die| die.result >= threshold)
+            .map(Die::another)
+            .collect::<VecDeque<Die>>();
+        let mut exploded = Vec::new();
+
+        while let Some(die) = pending.pop_front() {
+            if exploded.len() >= MAX_DICE as usize { break; }
+
+            if die.result >= threshold { pending.push_back(die.another()); }
+            exploded.push(die);
+        }
+
+        Pool { dice: exploded }
+    }
+
+    // Rerolls every die below the threshold once, keeping each die where it was
+    pub fn reroll_below(&self, threshold: u32) -> Pool {
+        let mut dice = self.dice.clone();
+        for die in dice.iter_mut().filter(|die| die.result < threshold) {
+            die.reroll();
+        }
+
+        Pool { dice }
+    }
+
+    // Rerolls every die not showing one of the faces, keeping each die where it was
+    pub fn reroll_except(&self, faces: &[u32]) -> Pool {
+        let mut dice = self.dice.clone();
+        for die in dice.iter_mut().filter(|die| !faces.contains(&die.result)) {
+            die.reroll();
+        }
+
+        Pool { dice }
+    }
+
+    // One block per die, scaled by how high it rolled on its own sides
+    pub fn sparkline(&self) -> String {
+        self.dice
+            .iter()
+            .map(|die| {
+                if die.sides <= 1 { return SPARK_BLOCKS[SPARK_BLOCKS.len() - 1]; }
+                let level = (die.result - 1) as usize * (SPARK_BLOCKS.len() - 1) / (die.sides - 1) as usize;
+                SPARK_BLOCKS[level]
+            })
+            .collect()
+    }
+
+    pub fn merge(mut self, other: Pool) -> Pool {
+        self.dice.extend(other.dice);
+
+        self
+    }
+}
+
+// Composes a roll without going through dice notation, like `Pool::builder().dice(8, 10).explode_on(10).target(7).build()`.
+// Out of range counts and sides are clamped rather than rejected, the same as the game system rolls.
+#[derive(Debug, Clone, Default)]
+pub struct PoolBuilder {
+    count: u32,
+    sides: u32,
+    explode_threshold: Option<u32>,
+    target: Option<u32>,
+}
+
+impl PoolBuilder {
+    pub fn dice(mut self, count: u32, sides: u32) -> PoolBuilder {
+        self.count = count.min(MAX_DICE);
+        self.sides = sides.clamp(1, MAX_SIDES);
+        self
+    }
+
+    pub fn explode_on(mut self, threshold: u32) -> PoolBuilder {
+        self.explode_threshold = Some(threshold);
+        self
+    }
+
+    pub fn target(mut self, target: u32) -> PoolBuilder {
+        self.target = Some(target);
+        self
+    }
+
+    pub fn build(self) -> PoolRoll {
+        // The sides were clamped to at least one already
+        let dice = Pool::rolled(self.count, self.sides);
+        let exploded = match self.explode_threshold {
+            Some(threshold) => dice.explode(threshold),
+            None => Pool::default(),
+        };
+
+        PoolRoll { dice, exploded, target: self.target }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PoolRoll {
+    pub dice: Pool,
+    // Only the extra dice from explosions, so callers can tell them apart from the original pool
+    pub exploded: Pool,
+    pub target: Option<u32>,
+}
+
+impl PoolRoll {
+    // Dice at or above the target across the original pool and its explosions. Without a target nothing succeeds.
+    pub fn successes(&self) -> u32 {
+        match self.target {
+            Some(target) => self.dice.count_at_least(target) + self.exploded.count_at_least(target),
+            None => 0,
+        }
+    }
+}
+
+impl fmt::Display for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let results = self.dice.iter().map(|die| die.to_string()).collect::<Vec<String>>();
+        write!(f, "[{}]", results.join(", "))
+    }
+}
+
+// Parses dice notation like `3d6`, `d20` or `d%`. Either side can also be an array, which rolls every combination
+// into one pool: `[2,3]d6` is 2d6 and 3d6, `2d[6,10]` is 2d6 and 2d10.
+impl FromStr for Pool {
+    type Err = RollError;
+
+    fn from_str(s: &str) -> Result<Pool, RollError> {
+        let notation = s.split_whitespace().collect::<String>().to_lowercase();
+
+        let (count_str, sides_str) = notation
+            .split_once('d')
+            .ok_or_else(|| RollError::Syntax(format!("`{}` isn't dice, try something like `3d6`", notation)))?;
+
+        // A missing count means a single die, so `d20` is the same as `1d20`, and `d%` is a d100
+        let sides_str = if sides_str == "%" { "100" } else { sides_str };
+        let counts = if count_str.is_empty() { vec![1] } else { parse_numbers(count_str, "number of dice")? };
+        let sides = parse_numbers(sides_str, "number of sides")?;
+
+        if let Some(zero) = counts.iter().find(|&&count| count == 0) {
+            return Err(RollError::Syntax(format!("can't roll {} dice", zero)));
+        }
+        if let Some(bad) = sides.iter().find(|&&side| side == 0 || side > MAX_SIDES) {
+            return Err(RollError::Syntax(format!("dice need between 1 and {} sides, not {}", MAX_SIDES, bad)));
+        }
+
+        let total_dice = counts.iter().map(|&count| u64::from(count)).sum::<u64>() * sides.len() as u64;
+        if total_dice > u64::from(MAX_DICE) {
+            return Err(RollError::TooManyDice(total_dice));
+        }
+
+        let pools = counts
+            .iter()
+            .flat_map(|&count| sides.iter().map(move |&side| Pool::new(count, side)))
+            .collect::<Result<Vec<Pool>, RollError>>()?;
+
+        Ok(pools.into_iter().fold(Pool::default(), Pool::merge))
+    }
+}
+
+fn parse_numbers(part: &str, what: &str) -> Result<Vec<u32>, RollError> {
+    let list = match part.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+        Some(list) => list.split(',').collect::<Vec<&str>>(),
+        None => vec![part],
+    };
+
+    list.iter()
+        .map(|number| number.parse::<u32>().map_err(|why| RollError::Syntax(format!("invalid {} `{}`: {}", what, number, why))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sides(pool: &Pool) -> Vec<u32> {
+        pool.dice().iter().map(|die| die.sides).collect()
+    }
+
+    #[test]
+    fn rerolls_only_the_chosen_dice_in_place() {
+        let pool = Pool::from_results(10, &[3, 9, 10]);
+
+        let rerolled = pool.reroll_below(8);
+        assert_eq!(rerolled.dice()[0].previous, vec![3]);
+        assert_eq!(&rerolled.dice()[1..], &pool.dice()[1..]);
+
+        let pushed = pool.reroll_except(&[10]);
+        assert_eq!(pushed.dice()[1].previous, vec![9]);
+        assert_eq!(pushed.dice()[2], pool.dice()[2]);
+    }
+
+    #[test]
+    fn rerolling_twice_shows_both_steps() {
+        let twice = Pool::from_results(1, &[1]).reroll_below(2).reroll_below(2);
+
+        assert_eq!(twice.to_string(), "[1 → 1 → 1]");
+    }
+
+    #[test]
+    fn parses_counts_and_sides() {
+        let pool = "3d6".parse::<Pool>().unwrap();
+        assert_eq!(sides(&pool), vec![6, 6, 6]);
+        assert!(pool.dice().iter().all(|die| (1..=6).contains(&die.result)));
+
+        assert_eq!(sides(&"d20".parse::<Pool>().unwrap()), vec![20]);
+        assert_eq!(sides(&" 2 D 8 ".parse::<Pool>().unwrap()), vec![8, 8]);
+    }
+
+    #[test]
+    fn arrays_roll_every_combination() {
+        assert_eq!(sides(&"[2,3]d6".parse::<Pool>().unwrap()), vec![6; 5]);
+        assert_eq!(sides(&"2d[6,10]".parse::<Pool>().unwrap()), vec![6, 6, 10, 10]);
+        assert_eq!(sides(&"[1,2]d[4,8]".parse::<Pool>().unwrap()), vec![4, 8, 4, 4, 8, 8]);
+    }
+
+    #[test]
+    fn rejects_bad_notation() {
+        for notation in ["", "6", "d", "xd6", "3dx", "3d0", "0d6", "[2,0]d6", "[2,3d6", "-1d6"] {
+            assert!(notation.parse::<Pool>().is_err(), "{}", notation);
+        }
+        assert!(format!("1d{}", MAX_SIDES + 1).parse::<Pool>().is_err());
+        assert_eq!(format!("{}d6", MAX_DICE + 1).parse::<Pool>(), Err(RollError::TooManyDice(u64::from(MAX_DICE) + 1)));
+        assert_eq!("[600,600]d6".parse::<Pool>(), Err(RollError::TooManyDice(1200)));
+    }
+
+    #[test]
+    fn keeps_the_highest_or_lowest_in_roll_order() {
+        let pool = Pool::from_results(6, &[2, 6, 4, 1]);
+
+        assert_eq!(pool.keep_highest(2), Pool::from_results(6, &[6, 4]));
+        assert_eq!(pool.keep_lowest(2), Pool::from_results(6, &[2, 1]));
+        assert_eq!(pool.keep_highest(10), pool);
+        assert!(pool.keep_lowest(0).is_empty());
+    }
+
+    #[test]
+    fn keeping_ties_still_splits_the_pool() {
+        let pool = Pool::from_results(6, &[3, 3, 3]);
+
+        assert_eq!(pool.keep_highest(2).dice().len() + pool.keep_lowest(1).dice().len(), 3);
+    }
+
+    #[test]
+    fn drops_the_highest_lowest_or_exact_faces() {
+        let pool = Pool::from_results(6, &[1, 6, 4, 1]);
+
+        assert_eq!(pool.drop_highest(1), Pool::from_results(6, &[1, 4, 1]));
+        assert_eq!(pool.drop_lowest(2), Pool::from_results(6, &[6, 4]));
+        assert_eq!(pool.drop_exact(&[1]), Pool::from_results(6, &[6, 4]));
+        assert_eq!(pool.keep_exact(&[1, 4]), Pool::from_results(6, &[1, 4, 1]));
+        assert!(pool.drop_lowest(10).is_empty());
+    }
+
+    #[test]
+    fn sorting_only_changes_the_order() {
+        let pool = Pool::from_results(6, &[4, 1, 6]);
+
+        assert_eq!(pool.sorted(false), Pool::from_results(6, &[1, 4, 6]));
+        assert_eq!(pool.sorted(true), Pool::from_results(6, &[6, 4, 1]));
+        assert_eq!(pool.sorted(true).total(), pool.total());
+    }
+
+    #[test]
+    fn percent_is_a_d100() {
+        assert_eq!(sides(&"d%".parse::<Pool>().unwrap()), vec![100]);
+        assert_eq!(sides(&"2d%".parse::<Pool>().unwrap()), vec![100, 100]);
+    }
+
+    #[test]
+    fn counts_results_at_or_above_a_target() {
+        let pool = Pool::from_results(10, &[8, 10, 3, 1, 10]);
+
+        assert_eq!(pool.count_at_least(8), 3);
+        assert_eq!(pool.count_exactly(10), 2);
+        assert_eq!(pool.count_exactly(5), 0);
+    }
+
+    #[test]
+    fn only_dice_at_the_threshold_explode() {
+        assert!(Pool::from_results(10, &[9, 3]).explode(10).is_empty());
+        assert!(Pool::from_results(10, &[10, 10]).explode(10).dice().len() >= 2);
+
+        // A d1 always meets a threshold of 1, so this only stops at the cap
+        assert_eq!(Pool::from_results(1, &[1]).explode(1).dice().len(), MAX_DICE as usize);
+    }
+
+    #[test]
+    fn builder_clamps_instead_of_rejecting() {
+        assert_eq!(Pool::builder().dice(MAX_DICE + 1, 6).build().dice.dice().len(), MAX_DICE as usize);
+        assert!(Pool::builder().dice(3, 0).build().dice.dice().iter().all(|die| die.sides == 1));
+        assert!(Pool::builder().build().dice.is_empty());
+    }
+
+    #[test]
+    fn builder_counts_successes_across_explosions() {
+        let roll = Pool::builder().dice(5, 1).explode_on(2).target(1).build();
+        assert!(roll.exploded.is_empty());
+        assert_eq!(roll.successes(), 5);
+
+        let roll = Pool::builder().dice(2, 1).explode_on(1).target(1).build();
+        assert_eq!(roll.successes(), 2 + MAX_DICE);
+
+        assert_eq!(Pool::builder().dice(3, 6).build().successes(), 0);
+    }
+}
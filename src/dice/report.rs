@@ -0,0 +1,76 @@
+use super::{
+    roll::RepeatedRoll,
+    RollError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Normal,
+    // Nothing was rolled, the annotations say why
+    Error,
+}
+
+// One roll of a repeated roll, already written out in each of the ways a frontend might have room for
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportField {
+    pub breakdown: String,
+    pub compact: String,
+    pub total: i64,
+}
+
+// Everything a frontend needs to show a roll its own way, so none of them have to pick apart a finished reply
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollReport {
+    // The sum of every roll, or None if nothing could be rolled
+    pub value: Option<i64>,
+    pub fields: Vec<ReportField>,
+    // Notes that go with the roll, like which saved roll was used, or what went wrong
+    pub annotations: Vec<String>,
+    pub severity: Severity,
+}
+
+impl RollReport {
+    pub fn from_rolls(repeated: &RepeatedRoll) -> RollReport {
+        let fields = repeated.rolls
+            .iter()
+            .map(|roll| ReportField { breakdown: roll.to_string(), compact: roll.compact_breakdown(), total: roll.total() })
+            .collect();
+
+        RollReport { value: Some(repeated.sum()), fields, annotations: Vec::new(), severity: Severity::Normal }
+    }
+
+    pub fn from_error(why: &RollError) -> RollReport {
+        RollReport { value: None, fields: Vec::new(), annotations: vec![why.to_string()], severity: Severity::Error }
+    }
+
+    pub fn annotate(mut self, annotation: String) -> RollReport {
+        self.annotations.push(annotation);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_every_roll_and_their_sum() {
+        let report = RollReport::from_rolls(&"3#2d1+1".parse::<RepeatedRoll>().unwrap());
+
+        assert_eq!(report.value, Some(9));
+        assert_eq!(report.severity, Severity::Normal);
+        assert_eq!(report.fields.len(), 3);
+        assert_eq!(report.fields[0].total, 3);
+        assert_eq!(report.fields[0].breakdown, "2d1 [1, 1] + 1");
+    }
+
+    #[test]
+    fn errors_keep_the_reason() {
+        let why = "2d".parse::<RepeatedRoll>().unwrap_err();
+        let report = RollReport::from_error(&why).annotate("`attack` is `2d`".to_string());
+
+        assert_eq!(report.value, None);
+        assert_eq!(report.severity, Severity::Error);
+        assert_eq!(report.annotations, vec![why.to_string(), "`attack` is `2d`".to_string()]);
+    }
+}
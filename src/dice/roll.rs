@@ -0,0 +1,543 @@
+use std::{
+    fmt,
+    str::FromStr,
+};
+
+use super::{
+    composite::{
+        self,
+        CompositePool,
+    },
+    die::Die,
+    fudge::{
+        self,
+        FudgePool,
+    },
+    pool::{
+        MAX_DICE,
+        Pool,
+    },
+    systems::ore::OreRoll,
+    RollError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sign {
+    Plus,
+    Minus,
+}
+
+impl fmt::Display for Sign {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Sign::Plus => write!(f, "+"),
+            Sign::Minus => write!(f, "-"),
+        }
+    }
+}
+
+pub const MAX_REPEATS: u32 = 20;
+
+// Counts the dice that meet a target as successes instead of adding them up, like `10d10t7`. Dice that also
+// meet the doubles threshold count twice, like the 10s in `10d10t7d10`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuccessTarget {
+    pub target: u32,
+    pub double: Option<u32>,
+}
+
+impl SuccessTarget {
+    pub fn successes(&self, pool: &Pool) -> u32 {
+        let doubles = self.double.map(|double| pool.count_at_least(double)).unwrap_or(0);
+
+        pool.count_at_least(self.target) + doubles
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    // Dropped dice are only kept around to show them in the breakdown
+    Dice { kept: Pool, dropped: Pool, successes: Option<SuccessTarget> },
+    Fudge(FudgePool),
+    Composite(CompositePool),
+    // Dice grouped into matching sets, like `7d10m`, worth the width of the widest set
+    Matches(OreRoll),
+    Constant(u32),
+}
+
+// One signed piece of a roll expression, like the `+ 1d8` in `2d6 + 1d8 + 3`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollPart {
+    pub sign: Sign,
+    pub notation: String,
+    pub term: Term,
+}
+
+impl RollPart {
+    pub fn value(&self) -> i64 {
+        let value = match &self.term {
+            Term::Dice { kept, successes: Some(target), .. } => i64::from(target.successes(kept)),
+            Term::Dice { kept, successes: None, .. } => i64::from(kept.total()),
+            Term::Fudge(dice) => dice.total(),
+            Term::Composite(rolls) => i64::from(rolls.total()),
+            Term::Matches(sets) => i64::from(sets.widest().map(|set| set.width).unwrap_or(0)),
+            Term::Constant(constant) => i64::from(*constant),
+        };
+
+        match self.sign {
+            Sign::Plus => value,
+            Sign::Minus => -value,
+        }
+    }
+}
+
+// A roll expression made of dice pools and constants added or subtracted together, where every pool is
+// rolled separately so the breakdown can show each one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Roll {
+    pub parts: Vec<RollPart>,
+}
+
+impl Roll {
+    pub fn total(&self) -> i64 {
+        self.parts.iter().map(RollPart::value).sum()
+    }
+
+    pub fn dice_count(&self) -> u64 {
+        self.parts
+            .iter()
+            .map(|part| match &part.term {
+                Term::Dice { kept, dropped, .. } => (kept.dice().len() + dropped.dice().len()) as u64,
+                Term::Fudge(dice) => dice.dice().len() as u64,
+                Term::Composite(rolls) => rolls.dice_count(),
+                Term::Matches(sets) => sets.dice.dice().len() as u64,
+                Term::Constant(_) => 0,
+            })
+            .sum()
+    }
+
+    // Every numbered die that was rolled, including dropped ones
+    pub fn dice(&self) -> Vec<&Die> {
+        self.parts
+            .iter()
+            .flat_map(|part| -> Vec<&Die> {
+                match &part.term {
+                    Term::Dice { kept, dropped, .. } => kept.dice().iter().chain(dropped.dice()).collect(),
+                    Term::Composite(rolls) => rolls.dice().collect(),
+                    Term::Matches(sets) => sets.dice.dice().iter().collect(),
+                    Term::Fudge(_) | Term::Constant(_) => Vec::new(),
+                }
+            })
+            .collect()
+    }
+
+    // Same as the Display breakdown, but with a sparkline for each pool instead of listing the dice
+    pub fn compact_breakdown(&self) -> String {
+        self.breakdown(|pool| pool.sparkline())
+    }
+
+    fn breakdown(&self, show_pool: impl Fn(&Pool) -> String) -> String {
+        let mut breakdown = String::new();
+
+        for (i, part) in self.parts.iter().enumerate() {
+            match (i, part.sign) {
+                (0, Sign::Plus) => {},
+                (0, Sign::Minus) => breakdown.push('-'),
+                (_, sign) => breakdown.push_str(&format!(" {} ", sign)),
+            }
+
+            match &part.term {
+                Term::Dice { kept, dropped, .. } => {
+                    breakdown.push_str(&format!("{} {}", part.notation, show_pool(kept)));
+                    if !dropped.is_empty() {
+                        breakdown.push_str(&format!(" ~~{}~~", show_pool(dropped)));
+                    }
+                },
+                // Fudge dice are only one character each, so there's no sparkline for them
+                Term::Fudge(dice) => breakdown.push_str(&format!("{} {}", part.notation, dice)),
+                Term::Composite(rolls) => breakdown.push_str(&format!("{} {}", part.notation, rolls)),
+                Term::Matches(sets) => {
+                    let matched = sets.sets().iter().map(|set| set.to_string()).collect::<Vec<String>>();
+                    let matched = if matched.is_empty() { "no sets".to_string() } else { matched.join(" ") };
+                    breakdown.push_str(&format!("{} {} ({})", part.notation, show_pool(&sets.dice), matched));
+                },
+                Term::Constant(constant) => breakdown.push_str(&constant.to_string()),
+            }
+        }
+
+        breakdown
+    }
+}
+
+impl fmt::Display for Roll {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.breakdown(|pool| pool.to_string()))
+    }
+}
+
+impl FromStr for Roll {
+    type Err = RollError;
+
+    fn from_str(s: &str) -> Result<Roll, RollError> {
+        let expression = s.split_whitespace().collect::<String>().to_lowercase();
+        if expression.is_empty() {
+            return Err(RollError::Syntax("give me some dice to roll, like `2d6+3`".to_string()));
+        }
+
+        let mut parts = Vec::new();
+        for (sign, notation) in split_terms(&expression)? {
+            let term = if notation.ends_with("df") {
+                Term::Fudge(fudge::parse_fudge(notation)?)
+            } else if let Some(rolls) = composite::parse_composite(notation) {
+                Term::Composite(rolls?)
+            } else if notation.contains('d') {
+                parse_dice(notation)?
+            } else {
+                let constant = notation
+                    .parse::<u32>()
+                    .map_err(|why| RollError::Syntax(format!("`{}` isn't dice or a number: {}", notation, why)))?;
+                Term::Constant(constant)
+            };
+
+            parts.push(RollPart { sign, notation: notation.to_string(), term });
+        }
+
+        let roll = Roll { parts };
+        if roll.dice_count() > u64::from(MAX_DICE) {
+            return Err(RollError::TooManyDice(roll.dice_count()));
+        }
+
+        Ok(roll)
+    }
+}
+
+// The same roll expression rolled several times, like `3#4d6k3`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepeatedRoll {
+    pub rolls: Vec<Roll>,
+}
+
+impl RepeatedRoll {
+    pub fn sum(&self) -> i64 {
+        self.rolls.iter().map(Roll::total).sum()
+    }
+}
+
+impl FromStr for RepeatedRoll {
+    type Err = RollError;
+
+    fn from_str(s: &str) -> Result<RepeatedRoll, RollError> {
+        let (repeats, expression) = match s.split_once('#') {
+            Some((repeats_str, expression)) => {
+                let repeats_str = repeats_str.trim();
+                let repeats = repeats_str
+                    .parse::<u32>()
+                    .map_err(|why| RollError::Syntax(format!("invalid number of repeats `{}`: {}", repeats_str, why)))?;
+                if repeats == 0 || repeats > MAX_REPEATS {
+                    return Err(RollError::Syntax(format!("I can repeat a roll between 1 and {} times, not {}", MAX_REPEATS, repeats)));
+                }
+                (repeats, expression)
+            },
+            None => (1, s),
+        };
+
+        let rolls = (0..repeats).map(|_| expression.parse::<Roll>()).collect::<Result<Vec<Roll>, RollError>>()?;
+
+        let total_dice = rolls.iter().map(Roll::dice_count).sum::<u64>();
+        if total_dice > u64::from(MAX_DICE) {
+            return Err(RollError::TooManyDice(total_dice));
+        }
+
+        Ok(RepeatedRoll { rolls })
+    }
+}
+
+// Parses a dice term with an optional modifier choosing which dice count: `4d6k3` or `4d6kh3` keep the highest 3,
+// `2d20kl1` keeps the lowest, `4d6dl1` and `4d6dh1` drop the lowest or highest, and `6d6de1` or `6d6de[1,2]` drop
+// every die showing those faces. A target at the end counts successes instead, like `10d10t7` or `10d10t7d10`.
+// Ending with `s` or `sa` sorts the dice from lowest to highest in the breakdown, and `sd` from highest to lowest.
+// An `m` before any sorting groups plain dice into matching sets instead, like `7d10m` or `7d10msd`.
+fn parse_dice(notation: &str) -> Result<Term, RollError> {
+    let (notation, sort) = match notation.split_once('s') {
+        Some((notation, "")) | Some((notation, "a")) => (notation, Some(false)),
+        Some((notation, "d")) => (notation, Some(true)),
+        Some((_, sort_str)) => return Err(RollError::Syntax(format!("I don't know how to sort by `s{}`, try `sa` or `sd`", sort_str))),
+        None => (notation, None),
+    };
+
+    if let Some(pool_str) = notation.strip_suffix('m') {
+        // Sets are about every die that was rolled, so there's nothing to keep, drop or count first
+        let pool = pool_str.parse::<Pool>().map_err(|why| match why {
+            RollError::Syntax(_) => RollError::Syntax(format!("matches only work on plain dice like `7d10m`, not `{}m`", pool_str)),
+            other => other,
+        })?;
+        let pool = match sort {
+            Some(descending) => pool.sorted(descending),
+            None => pool,
+        };
+
+        return Ok(Term::Matches(OreRoll::from_pool(pool)));
+    }
+
+    let (dice_str, successes) = match notation.split_once('t') {
+        Some((dice_str, target_str)) => (dice_str, Some(parse_target(target_str)?)),
+        None => (notation, None),
+    };
+
+    let modifier_start = ["k", "dh", "dl", "de"].iter().filter_map(|modifier| dice_str.find(modifier)).min();
+    let (pool_str, modifier) = match modifier_start {
+        Some(start) => (&dice_str[..start], Some(&dice_str[start..])),
+        None => (dice_str, None),
+    };
+
+    let pool = pool_str.parse::<Pool>()?;
+    let (mut kept, mut dropped) = match modifier {
+        Some(modifier) => select_dice(&pool, modifier)?,
+        None => (pool, Pool::default()),
+    };
+    if let Some(descending) = sort {
+        kept = kept.sorted(descending);
+        dropped = dropped.sorted(descending);
+    }
+
+    Ok(Term::Dice { kept, dropped, successes })
+}
+
+// Splits a pool into the dice a keep or drop modifier counts and the ones it leaves out
+fn select_dice(pool: &Pool, modifier: &str) -> Result<(Pool, Pool), RollError> {
+    if let Some(faces_str) = modifier.strip_prefix("de") {
+        let faces = faces_str
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(',')
+            .map(|face| face.parse::<u32>().map_err(|why| RollError::Syntax(format!("invalid face to drop `{}`: {}", face, why))))
+            .collect::<Result<Vec<u32>, RollError>>()?;
+
+        return Ok((pool.drop_exact(&faces), pool.keep_exact(&faces)));
+    }
+
+    let (selection, count_str) = ["kh", "kl", "k", "dh", "dl"]
+        .iter()
+        .find_map(|selection| modifier.strip_prefix(selection).map(|count_str| (*selection, count_str)))
+        .ok_or_else(|| RollError::Syntax(format!("I don't know the modifier `{}`", modifier)))?;
+    let count = count_str
+        .parse::<u32>()
+        .map_err(|why| RollError::Syntax(format!("invalid number of dice in `{}`: {}", modifier, why)))?;
+
+    let rolled = pool.dice().len() as u32;
+    let selected = match selection {
+        "kl" => (pool.keep_lowest(count), pool.keep_highest(rolled.saturating_sub(count))),
+        "dh" => (pool.drop_highest(count), pool.keep_highest(count)),
+        "dl" => (pool.drop_lowest(count), pool.keep_lowest(count)),
+        _ => (pool.keep_highest(count), pool.keep_lowest(rolled.saturating_sub(count))),
+    };
+
+    Ok(selected)
+}
+
+fn parse_target(target_str: &str) -> Result<SuccessTarget, RollError> {
+    let (target_str, double_str) = match target_str.split_once('d') {
+        Some((target_str, double_str)) => (target_str, Some(double_str)),
+        None => (target_str, None),
+    };
+
+    let target = target_str
+        .parse::<u32>()
+        .map_err(|why| RollError::Syntax(format!("invalid success target `{}`: {}", target_str, why)))?;
+    let double = match double_str {
+        Some(double_str) => {
+            let double = double_str
+                .parse::<u32>()
+                .map_err(|why| RollError::Syntax(format!("invalid doubles target `{}`: {}", double_str, why)))?;
+            if double < target {
+                return Err(RollError::Syntax(format!("doubles need to be at least the success target, so `d{}` can't go with `t{}`", double, target)));
+            }
+            Some(double)
+        },
+        None => None,
+    };
+
+    Ok(SuccessTarget { target, double })
+}
+
+// Splits an expression on + and -, leaving signs inside array brackets alone
+fn split_terms(expression: &str) -> Result<Vec<(Sign, &str)>, RollError> {
+    let mut terms = Vec::new();
+    let mut sign = Sign::Plus;
+    let mut start = 0;
+    let mut depth = 0;
+
+    for (i, c) in expression.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            '+' | '-' if depth == 0 => {
+                // A sign at the very start just sets the sign of the first term
+                if i > 0 {
+                    terms.push((sign, &expression[start..i]));
+                }
+                sign = if c == '+' { Sign::Plus } else { Sign::Minus };
+                start = i + 1;
+            },
+            _ => {},
+        }
+    }
+    terms.push((sign, &expression[start..]));
+
+    if terms.iter().any(|(_, term)| term.is_empty()) {
+        return Err(RollError::Syntax(format!("`{}` has a sign without anything to add or subtract", expression)));
+    }
+
+    Ok(terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kept_and_dropped(notation: &str) -> (Pool, Pool) {
+        match notation.parse::<Roll>().unwrap().parts.remove(0).term {
+            Term::Dice { kept, dropped, .. } => (kept, dropped),
+            term => panic!("{} parsed as {:?}", notation, term),
+        }
+    }
+
+    // A d1 always rolls 1, so these totals are known ahead of time
+    #[test]
+    fn adds_and_subtracts_every_term() {
+        assert_eq!("3d1+2".parse::<Roll>().unwrap().total(), 5);
+        assert_eq!("1d1 + 1d1 - 3".parse::<Roll>().unwrap().total(), -1);
+        assert_eq!("-2d1".parse::<Roll>().unwrap().total(), -2);
+        assert_eq!("[2,3]d1-1".parse::<Roll>().unwrap().total(), 4);
+    }
+
+    #[test]
+    fn keeps_each_pool_separate() {
+        let roll = "2d1+1d1+3".parse::<Roll>().unwrap();
+
+        let notations = roll.parts.iter().map(|part| part.notation.as_str()).collect::<Vec<&str>>();
+        assert_eq!(notations, vec!["2d1", "1d1", "3"]);
+        assert_eq!(roll.dice_count(), 3);
+        assert_eq!(roll.to_string(), "2d1 [1, 1] + 1d1 [1] + 3");
+    }
+
+    #[test]
+    fn rejects_bad_expressions() {
+        for expression in ["", "  ", "2d6+", "2d6++1", "+", "2d6x", "abc"] {
+            assert!(expression.parse::<Roll>().is_err(), "{:?}", expression);
+        }
+        assert_eq!("600d6+600d6".parse::<Roll>(), Err(RollError::TooManyDice(1200)));
+    }
+
+    #[test]
+    fn keep_modifiers_split_off_the_rest() {
+        for notation in ["4d6k3", "4d6kh3"] {
+            let (kept, dropped) = kept_and_dropped(notation);
+            assert_eq!((kept.dice().len(), dropped.dice().len()), (3, 1), "{}", notation);
+            assert!(kept.dice().iter().all(|die| die.result >= dropped.dice()[0].result), "{}", notation);
+        }
+
+        let (kept, dropped) = kept_and_dropped("2d20kl1");
+        assert!(kept.dice()[0].result <= dropped.dice()[0].result);
+
+        assert!("2d6k".parse::<Roll>().is_err());
+        assert!("2d6kx".parse::<Roll>().is_err());
+    }
+
+    #[test]
+    fn repeats_the_whole_expression() {
+        let repeated = "3#2d1+1".parse::<RepeatedRoll>().unwrap();
+        assert_eq!(repeated.rolls.len(), 3);
+        assert_eq!(repeated.sum(), 9);
+
+        assert_eq!("2d1".parse::<RepeatedRoll>().unwrap().rolls.len(), 1);
+        assert!("0#d6".parse::<RepeatedRoll>().is_err());
+        assert!("x#d6".parse::<RepeatedRoll>().is_err());
+        assert!(format!("{}#d6", MAX_REPEATS + 1).parse::<RepeatedRoll>().is_err());
+        assert_eq!("2#600d6".parse::<RepeatedRoll>(), Err(RollError::TooManyDice(1200)));
+    }
+
+    #[test]
+    fn drop_modifiers_move_dice_to_the_dropped_pool() {
+        let (kept, dropped) = kept_and_dropped("4d6dl1");
+        assert_eq!((kept.dice().len(), dropped.dice().len()), (3, 1));
+        assert!(kept.dice().iter().all(|die| die.result >= dropped.dice()[0].result));
+
+        let (kept, dropped) = kept_and_dropped("2d20dh1");
+        assert!(kept.dice()[0].result <= dropped.dice()[0].result);
+
+        let (kept, dropped) = kept_and_dropped("20d6de[1,2]");
+        assert!(kept.dice().iter().all(|die| die.result > 2));
+        assert!(dropped.dice().iter().all(|die| die.result <= 2));
+        assert_eq!(kept.dice().len() + dropped.dice().len(), 20);
+
+        assert!("6d6de".parse::<Roll>().is_err());
+        assert!("6d6de[1,x]".parse::<Roll>().is_err());
+    }
+
+    #[test]
+    fn sort_modifiers_order_the_breakdown() {
+        let ascending = |pool: &Pool| pool.dice().windows(2).all(|pair| pair[0].result <= pair[1].result);
+
+        for notation in ["10d6s", "10d6sa"] {
+            assert!(ascending(&kept_and_dropped(notation).0), "{}", notation);
+        }
+        let (kept, dropped) = kept_and_dropped("10d6k5sd");
+        assert_eq!(kept, kept.sorted(true));
+        assert_eq!(dropped, dropped.sorted(true));
+
+        assert!("10d6sx".parse::<Roll>().is_err());
+    }
+
+    #[test]
+    fn doubles_count_twice() {
+        let pool = Pool::from_results(10, &[10, 7, 3, 9, 10]);
+
+        assert_eq!(SuccessTarget { target: 7, double: None }.successes(&pool), 4);
+        assert_eq!(SuccessTarget { target: 7, double: Some(10) }.successes(&pool), 6);
+        assert_eq!(SuccessTarget { target: 7, double: Some(9) }.successes(&pool), 7);
+    }
+
+    #[test]
+    fn targets_count_successes_instead_of_adding() {
+        assert_eq!("4d1t1".parse::<Roll>().unwrap().total(), 4);
+        assert_eq!("4d1t1d1".parse::<Roll>().unwrap().total(), 8);
+        assert_eq!("4d1t2".parse::<Roll>().unwrap().total(), 0);
+        assert_eq!("4d1t1+2".parse::<Roll>().unwrap().total(), 6);
+
+        assert!("10d10t".parse::<Roll>().is_err());
+        assert!("10d10t7d".parse::<Roll>().is_err());
+        assert!("10d10t7d6".parse::<Roll>().is_err());
+    }
+
+    #[test]
+    fn d66_is_composite_but_d6_and_d660_are_not() {
+        let roll = "2d66+1".parse::<Roll>().unwrap();
+        assert!(matches!(roll.parts[0].term, Term::Composite(_)));
+        assert_eq!(roll.dice_count(), 4);
+
+        assert!(matches!("d6".parse::<Roll>().unwrap().parts[0].term, Term::Dice { .. }));
+        assert!(matches!("d660".parse::<Roll>().unwrap().parts[0].term, Term::Dice { .. }));
+    }
+
+    #[test]
+    fn matches_are_worth_the_widest_set() {
+        let roll = "5d1m+2".parse::<Roll>().unwrap();
+        assert_eq!(roll.total(), 7);
+        assert_eq!(roll.dice_count(), 5);
+        assert!(roll.to_string().starts_with("5d1m [1, 1, 1, 1, 1] (5x1)"), "{}", roll);
+
+        let single = "1d6m".parse::<Roll>().unwrap();
+        assert_eq!(single.total(), 0);
+        assert!(single.to_string().ends_with("(no sets)"), "{}", single);
+    }
+
+    #[test]
+    fn matches_only_work_on_plain_dice() {
+        assert!(matches!("7d10m".parse::<Roll>().unwrap().parts[0].term, Term::Matches(_)));
+        assert!("7d10msd".parse::<Roll>().is_ok());
+        assert!("7d10k5m".parse::<Roll>().is_err());
+        assert!("7d10t7m".parse::<Roll>().is_err());
+        assert!("7d10sm".parse::<Roll>().is_err());
+    }
+}
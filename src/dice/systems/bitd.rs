@@ -0,0 +1,102 @@
+use std::fmt;
+
+use crate::dice::pool::{
+    MAX_DICE,
+    Pool,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BitdResult {
+    Critical,
+    Success,
+    Partial,
+    Failure,
+}
+
+impl fmt::Display for BitdResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            BitdResult::Critical => "Critical",
+            BitdResult::Success => "Success",
+            BitdResult::Partial => "Partial success",
+            BitdResult::Failure => "Failure",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Blades in the Dark (and other Forged in the Dark games) roll: only the highest d6 counts
+#[derive(Debug, Clone)]
+pub struct BitdRoll {
+    pub dice: Pool,
+    pub kept: Pool,
+    // No dice rolls two and keeps the lower one instead, which can't crit
+    pub zero_dice: bool,
+}
+
+impl BitdRoll {
+    pub fn new(count: u32) -> BitdRoll {
+        if count == 0 {
+            let dice = Pool::rolled(2, 6);
+            let kept = dice.keep_lowest(1);
+            return BitdRoll { dice, kept, zero_dice: true };
+        }
+
+        let dice = Pool::rolled(count.min(MAX_DICE), 6);
+        let kept = dice.keep_highest(1);
+
+        BitdRoll { dice, kept, zero_dice: false }
+    }
+
+    pub fn result(&self) -> BitdResult {
+        if !self.zero_dice && self.dice.count_exactly(6) >= 2 {
+            return BitdResult::Critical;
+        }
+
+        match self.kept.total() {
+            6 => BitdResult::Success,
+            4 | 5 => BitdResult::Partial,
+            _ => BitdResult::Failure,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_highest_die_decides() {
+        let cases: [(&[u32], BitdResult); 6] = [
+            (&[6, 2, 6], BitdResult::Critical),
+            (&[6, 3], BitdResult::Success),
+            (&[5, 1, 4], BitdResult::Partial),
+            (&[4], BitdResult::Partial),
+            (&[3, 2, 1], BitdResult::Failure),
+            (&[1], BitdResult::Failure),
+        ];
+
+        for (results, expected) in cases.iter() {
+            let dice = Pool::from_results(6, results);
+            let roll = BitdRoll { kept: dice.keep_highest(1), dice, zero_dice: false };
+            assert_eq!(roll.result(), *expected, "{:?}", results);
+        }
+    }
+
+    #[test]
+    fn zero_dice_keep_the_lower_and_never_crit() {
+        let dice = Pool::from_results(6, &[6, 6]);
+        let sixes = BitdRoll { kept: dice.keep_lowest(1), dice, zero_dice: true };
+        assert_eq!(sixes.result(), BitdResult::Success);
+
+        let roll = BitdRoll::new(0);
+        assert!(roll.zero_dice);
+        assert_eq!(roll.dice.dice().len(), 2);
+        assert_eq!(roll.kept.total(), roll.dice.dice().iter().map(|die| die.result).min().unwrap());
+    }
+
+    #[test]
+    fn caps_the_pool() {
+        assert_eq!(BitdRoll::new(u32::MAX).dice.dice().len(), MAX_DICE as usize);
+    }
+}
@@ -0,0 +1,155 @@
+use std::fmt;
+
+use crate::dice::pool::Pool;
+
+// The rules only ever call for up to two bonus or penalty dice
+pub const MAX_EXTRA_DICE: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CocResult {
+    Critical,
+    Extreme,
+    Hard,
+    Regular,
+    Failure,
+    Fumble,
+}
+
+impl fmt::Display for CocResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CocResult::Critical => "Critical success",
+            CocResult::Extreme => "Extreme success",
+            CocResult::Hard => "Hard success",
+            CocResult::Regular => "Regular success",
+            CocResult::Failure => "Failure",
+            CocResult::Fumble => "Fumble",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Call of Cthulhu 7e skill roll: a d100 rolled as a tens die and a units die. Bonus dice roll extra tens dice and
+// keep the best, penalty dice keep the worst.
+#[derive(Debug, Clone)]
+pub struct CocRoll {
+    pub skill: u32,
+    pub tens: Pool,
+    pub units: Pool,
+    // Positive for bonus dice, negative for penalty dice
+    pub extra_dice: i32,
+}
+
+impl CocRoll {
+    pub fn new(skill: u32, extra_dice: i32) -> CocRoll {
+        let extra_dice = extra_dice.clamp(-(MAX_EXTRA_DICE as i32), MAX_EXTRA_DICE as i32);
+
+        CocRoll {
+            skill,
+            tens: Pool::rolled(1 + extra_dice.unsigned_abs(), 10),
+            units: Pool::rolled(1, 10),
+            extra_dice,
+        }
+    }
+
+    // Every tens die read with the units die, from 1 to 100. A 0 on both is 100, not 0.
+    pub fn candidates(&self) -> Vec<u32> {
+        let units = self.units.total() % 10;
+
+        self.tens
+            .dice()
+            .iter()
+            .map(|die| match (die.result % 10) * 10 + units {
+                0 => 100,
+                value => value,
+            })
+            .collect()
+    }
+
+    pub fn value(&self) -> u32 {
+        let candidates = self.candidates().into_iter();
+        let value = if self.extra_dice >= 0 { candidates.min() } else { candidates.max() };
+
+        value.unwrap_or(100)
+    }
+
+    pub fn result(&self) -> CocResult {
+        let value = self.value();
+        // Below 50 skill, anything from 96 up fumbles
+        let fumble = if self.skill < 50 { 96 } else { 100 };
+
+        if value == 1 {
+            CocResult::Critical
+        } else if value >= fumble {
+            CocResult::Fumble
+        } else if value <= self.skill / 5 {
+            CocResult::Extreme
+        } else if value <= self.skill / 2 {
+            CocResult::Hard
+        } else if value <= self.skill {
+            CocResult::Regular
+        } else {
+            CocResult::Failure
+        }
+    }
+
+    // How far under the skill the roll was, negative when it failed
+    pub fn margin(&self) -> i64 {
+        i64::from(self.skill) - i64::from(self.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tens dice are d10s read as 10, 20 ... with a 10 meaning 00, and the units die the same way
+    fn coc(skill: u32, extra_dice: i32, tens: &[u32], units: u32) -> CocRoll {
+        CocRoll { skill, tens: Pool::from_results(10, tens), units: Pool::from_results(10, &[units]), extra_dice }
+    }
+
+    #[test]
+    fn reads_tens_and_units_as_a_d100() {
+        assert_eq!(coc(50, 0, &[4], 2).value(), 42);
+        assert_eq!(coc(50, 0, &[10], 5).value(), 5);
+        assert_eq!(coc(50, 0, &[3], 10).value(), 30);
+        assert_eq!(coc(50, 0, &[10], 10).value(), 100);
+    }
+
+    #[test]
+    fn bonus_dice_keep_the_lowest_and_penalty_dice_the_highest() {
+        assert_eq!(coc(50, 1, &[3, 7], 5).value(), 35);
+        assert_eq!(coc(50, -2, &[3, 7, 1], 5).value(), 75);
+        assert_eq!(coc(50, -1, &[10, 3], 10).value(), 100);
+    }
+
+    #[test]
+    fn grades_the_roll_against_the_skill() {
+        assert_eq!(coc(50, 0, &[10], 1).result(), CocResult::Critical);
+        assert_eq!(coc(50, 0, &[1], 10).result(), CocResult::Extreme);
+        assert_eq!(coc(50, 0, &[2], 5).result(), CocResult::Hard);
+        assert_eq!(coc(50, 0, &[5], 10).result(), CocResult::Regular);
+        assert_eq!(coc(50, 0, &[5], 1).result(), CocResult::Failure);
+        assert_eq!(coc(50, 0, &[9], 9).result(), CocResult::Failure);
+        assert_eq!(coc(50, 0, &[10], 10).result(), CocResult::Fumble);
+    }
+
+    #[test]
+    fn low_skills_fumble_from_96() {
+        assert_eq!(coc(49, 0, &[9], 6).result(), CocResult::Fumble);
+        assert_eq!(coc(49, 0, &[9], 5).result(), CocResult::Failure);
+        assert_eq!(coc(100, 0, &[9], 9).result(), CocResult::Regular);
+    }
+
+    #[test]
+    fn margin_is_negative_on_a_failure() {
+        assert_eq!(coc(50, 0, &[4], 2).margin(), 8);
+        assert_eq!(coc(50, 0, &[6], 10).margin(), -10);
+    }
+
+    #[test]
+    fn clamps_the_extra_dice() {
+        assert_eq!(CocRoll::new(50, 5).tens.dice().len(), 1 + MAX_EXTRA_DICE as usize);
+        assert_eq!(CocRoll::new(50, i32::MIN).extra_dice, -(MAX_EXTRA_DICE as i32));
+    }
+}
@@ -0,0 +1,69 @@
+use crate::dice::pool::{
+    MAX_DICE,
+    Pool,
+};
+
+pub const DEFAULT_TARGET: u32 = 7;
+pub const DEFAULT_DOUBLE: u32 = 10;
+pub const STUNT_DICE: u32 = 2;
+
+// Exalted 3e roll: a pool of d10s where every die at or above the target is a success, and every die at or above
+// the double threshold counts as two
+#[derive(Debug, Clone)]
+pub struct ExaltedRoll {
+    pub dice: Pool,
+    pub target: u32,
+    pub double: u32,
+    pub stunt: bool,
+}
+
+impl ExaltedRoll {
+    pub fn new(pool_size: u32, target: u32, double: u32, stunt: bool) -> ExaltedRoll {
+        let count = pool_size.saturating_add(if stunt { STUNT_DICE } else { 0 }).min(MAX_DICE);
+
+        ExaltedRoll { dice: Pool::rolled(count, 10), target, double, stunt }
+    }
+
+    pub fn successes(&self) -> u32 {
+        self.dice
+            .dice()
+            .iter()
+            .map(|die| if die.result >= self.double { 2 } else if die.result >= self.target { 1 } else { 0 })
+            .sum()
+    }
+
+    pub fn is_botch(&self) -> bool {
+        self.successes() == 0 && self.dice.count_exactly(1) > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tens_count_double_and_sevens_count_once() {
+        let roll = ExaltedRoll { dice: Pool::from_results(10, &[10, 7, 6, 9, 1]), target: DEFAULT_TARGET, double: DEFAULT_DOUBLE, stunt: false };
+        assert_eq!(roll.successes(), 4);
+        assert!(!roll.is_botch());
+
+        let lowered = ExaltedRoll { target: 6, double: 9, ..roll };
+        assert_eq!(lowered.successes(), 6);
+    }
+
+    #[test]
+    fn a_one_with_no_successes_is_a_botch() {
+        let botch = ExaltedRoll { dice: Pool::from_results(10, &[1, 3, 6]), target: DEFAULT_TARGET, double: DEFAULT_DOUBLE, stunt: false };
+        assert!(botch.is_botch());
+
+        let failure = ExaltedRoll { dice: Pool::from_results(10, &[2, 3, 6]), ..botch };
+        assert!(!failure.is_botch());
+    }
+
+    #[test]
+    fn stunts_add_dice_within_the_cap() {
+        assert_eq!(ExaltedRoll::new(5, DEFAULT_TARGET, DEFAULT_DOUBLE, true).dice.dice().len(), 5 + STUNT_DICE as usize);
+        assert_eq!(ExaltedRoll::new(MAX_DICE, DEFAULT_TARGET, DEFAULT_DOUBLE, true).dice.dice().len(), MAX_DICE as usize);
+        assert_eq!(ExaltedRoll::new(u32::MAX, DEFAULT_TARGET, DEFAULT_DOUBLE, false).dice.dice().len(), MAX_DICE as usize);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::dice::fudge::FudgePool;
+
+pub const FATE_DICE: u32 = 4;
+
+// FATE roll: 4dF plus a skill rating, read off the ladder
+#[derive(Debug, Clone)]
+pub struct FateRoll {
+    pub dice: FudgePool,
+    pub skill: i64,
+}
+
+impl FateRoll {
+    pub fn new(skill: i64) -> FateRoll {
+        FateRoll { dice: FudgePool::new(FATE_DICE), skill }
+    }
+
+    pub fn total(&self) -> i64 {
+        self.dice.total().saturating_add(self.skill)
+    }
+}
+
+// The adjective for a result, like `Great` for +4. Anything off either end of the ladder gets the end name.
+pub fn ladder(value: i64) -> &'static str {
+    match value {
+        i64::MIN..=-4 => "Horrifying",
+        -3 => "Catastrophic",
+        -2 => "Terrible",
+        -1 => "Poor",
+        0 => "Mediocre",
+        1 => "Average",
+        2 => "Fair",
+        3 => "Good",
+        4 => "Great",
+        5 => "Superb",
+        6 => "Fantastic",
+        7 => "Epic",
+        _ => "Legendary",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_results_off_the_ladder() {
+        assert_eq!(ladder(-4), "Horrifying");
+        assert_eq!(ladder(i64::MIN), "Horrifying");
+        assert_eq!(ladder(0), "Mediocre");
+        assert_eq!(ladder(4), "Great");
+        assert_eq!(ladder(8), "Legendary");
+        assert_eq!(ladder(i64::MAX), "Legendary");
+    }
+
+    #[test]
+    fn adds_the_skill_without_overflowing() {
+        let roll = FateRoll::new(3);
+        assert_eq!(roll.dice.dice().len(), FATE_DICE as usize);
+        assert!((-1..=7).contains(&roll.total()));
+
+        assert_eq!(FateRoll { dice: FudgePool::default(), skill: i64::MAX }.total(), i64::MAX);
+    }
+}
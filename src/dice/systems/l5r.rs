@@ -0,0 +1,214 @@
+use std::fmt;
+
+use crate::dice::{
+    die::Die,
+    pool::MAX_DICE,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L5rDieKind {
+    Ring,
+    Skill,
+}
+
+impl L5rDieKind {
+    fn sides(self) -> u32 {
+        match self {
+            L5rDieKind::Ring => 6,
+            L5rDieKind::Skill => 12,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L5rSymbol {
+    Blank,
+    Opportunity,
+    OpportunityStrife,
+    Success,
+    SuccessStrife,
+    SuccessOpportunity,
+    Explosive,
+    ExplosiveStrife,
+}
+
+impl L5rSymbol {
+    fn from_face(kind: L5rDieKind, face: u32) -> L5rSymbol {
+        match (kind, face) {
+            (L5rDieKind::Ring, 2) => L5rSymbol::OpportunityStrife,
+            (L5rDieKind::Ring, 3) => L5rSymbol::Opportunity,
+            (L5rDieKind::Ring, 4) => L5rSymbol::SuccessStrife,
+            (L5rDieKind::Ring, 5) => L5rSymbol::Success,
+            (L5rDieKind::Ring, 6) => L5rSymbol::ExplosiveStrife,
+            (L5rDieKind::Skill, 3..=5) => L5rSymbol::Opportunity,
+            (L5rDieKind::Skill, 6..=7) => L5rSymbol::Success,
+            (L5rDieKind::Skill, 8) => L5rSymbol::SuccessOpportunity,
+            (L5rDieKind::Skill, 9..=10) => L5rSymbol::SuccessStrife,
+            (L5rDieKind::Skill, 11) => L5rSymbol::Explosive,
+            (L5rDieKind::Skill, 12) => L5rSymbol::ExplosiveStrife,
+            _ => L5rSymbol::Blank,
+        }
+    }
+
+    pub fn successes(self) -> u32 {
+        match self {
+            L5rSymbol::Success | L5rSymbol::SuccessStrife | L5rSymbol::SuccessOpportunity
+                | L5rSymbol::Explosive | L5rSymbol::ExplosiveStrife => 1,
+            _ => 0,
+        }
+    }
+
+    pub fn opportunities(self) -> u32 {
+        match self {
+            L5rSymbol::Opportunity | L5rSymbol::OpportunityStrife | L5rSymbol::SuccessOpportunity => 1,
+            _ => 0,
+        }
+    }
+
+    pub fn strife(self) -> u32 {
+        match self {
+            L5rSymbol::OpportunityStrife | L5rSymbol::SuccessStrife | L5rSymbol::ExplosiveStrife => 1,
+            _ => 0,
+        }
+    }
+
+    pub fn explodes(self) -> bool {
+        matches!(self, L5rSymbol::Explosive | L5rSymbol::ExplosiveStrife)
+    }
+
+    // How much a die is worth keeping when I have to choose for the player: successes first, then opportunities,
+    // and strife breaks ties by being worse
+    fn keep_priority(self) -> u32 {
+        self.explodes() as u32 * 8 + self.successes() * 4 + self.opportunities() * 2 + (1 - self.strife())
+    }
+}
+
+impl fmt::Display for L5rSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            L5rSymbol::Blank => "⬜",
+            L5rSymbol::Opportunity => "✨",
+            L5rSymbol::OpportunityStrife => "✨🔥",
+            L5rSymbol::Success => "⚔️",
+            L5rSymbol::SuccessStrife => "⚔️🔥",
+            L5rSymbol::SuccessOpportunity => "⚔️✨",
+            L5rSymbol::Explosive => "💥",
+            L5rSymbol::ExplosiveStrife => "💥🔥",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L5rDie {
+    pub kind: L5rDieKind,
+    pub symbol: L5rSymbol,
+}
+
+impl L5rDie {
+    pub fn new(kind: L5rDieKind) -> L5rDie {
+        let die = Die::rolled(kind.sides());
+
+        L5rDie { kind, symbol: L5rSymbol::from_face(kind, die.result) }
+    }
+}
+
+// Legend of the Five Rings 5e roll: ring and skill dice are rolled, then up to `keep` of them are kept.
+// Every kept explosive success adds another die of the same kind, which is kept too.
+#[derive(Debug, Clone)]
+pub struct L5rRoll {
+    pub rolled: Vec<L5rDie>,
+    pub kept: Vec<L5rDie>,
+    pub exploded: Vec<L5rDie>,
+}
+
+impl L5rRoll {
+    pub fn new(ring: u32, skill: u32, keep: u32) -> L5rRoll {
+        let ring = ring.min(MAX_DICE);
+        let skill = skill.min(MAX_DICE - ring);
+
+        let rolled = (0..ring)
+            .map(|_| L5rDie::new(L5rDieKind::Ring))
+            .chain((0..skill).map(|_| L5rDie::new(L5rDieKind::Skill)))
+            .collect::<Vec<L5rDie>>();
+
+        let mut kept = rolled.clone();
+        kept.sort_by_key(|die| std::cmp::Reverse(die.symbol.keep_priority()));
+        kept.truncate(keep as usize);
+
+        let mut exploded = Vec::new();
+        let mut pending = kept.iter().filter(|die| die.symbol.explodes()).map(|die| die.kind).collect::<Vec<L5rDieKind>>();
+        while let Some(kind) = pending.pop() {
+            if exploded.len() >= MAX_DICE as usize { break; }
+
+            let die = L5rDie::new(kind);
+            if die.symbol.explodes() { pending.push(kind); }
+            exploded.push(die);
+        }
+
+        L5rRoll { rolled, kept, exploded }
+    }
+
+    fn kept_symbols(&self) -> impl Iterator<Item = L5rSymbol> + '_ {
+        self.kept.iter().chain(self.exploded.iter()).map(|die| die.symbol)
+    }
+
+    pub fn successes(&self) -> u32 {
+        self.kept_symbols().map(L5rSymbol::successes).sum()
+    }
+
+    pub fn opportunities(&self) -> u32 {
+        self.kept_symbols().map(L5rSymbol::opportunities).sum()
+    }
+
+    pub fn strife(&self) -> u32 {
+        self.kept_symbols().map(L5rSymbol::strife).sum()
+    }
+}
+
+pub fn symbols(dice: &[L5rDie]) -> String {
+    dice.iter().map(|die| die.symbol.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faces_map_to_the_printed_symbols() {
+        assert_eq!(L5rSymbol::from_face(L5rDieKind::Ring, 1), L5rSymbol::Blank);
+        assert_eq!(L5rSymbol::from_face(L5rDieKind::Ring, 6), L5rSymbol::ExplosiveStrife);
+        assert_eq!(L5rSymbol::from_face(L5rDieKind::Skill, 2), L5rSymbol::Blank);
+        assert_eq!(L5rSymbol::from_face(L5rDieKind::Skill, 8), L5rSymbol::SuccessOpportunity);
+        assert_eq!(L5rSymbol::from_face(L5rDieKind::Skill, 11), L5rSymbol::Explosive);
+    }
+
+    #[test]
+    fn explosives_outrank_plain_successes_and_strife_breaks_ties() {
+        assert!(L5rSymbol::Explosive.keep_priority() > L5rSymbol::SuccessOpportunity.keep_priority());
+        assert!(L5rSymbol::Success.keep_priority() > L5rSymbol::SuccessStrife.keep_priority());
+        assert!(L5rSymbol::Opportunity.keep_priority() > L5rSymbol::Blank.keep_priority());
+    }
+
+    #[test]
+    fn totals_count_kept_and_exploded_dice_only() {
+        let die = |kind, symbol| L5rDie { kind, symbol };
+        let roll = L5rRoll {
+            rolled: vec![],
+            kept: vec![die(L5rDieKind::Ring, L5rSymbol::ExplosiveStrife), die(L5rDieKind::Skill, L5rSymbol::SuccessOpportunity)],
+            exploded: vec![die(L5rDieKind::Ring, L5rSymbol::OpportunityStrife)],
+        };
+
+        assert_eq!((roll.successes(), roll.opportunities(), roll.strife()), (2, 2, 2));
+        assert_eq!(symbols(&roll.exploded), "✨🔥");
+    }
+
+    #[test]
+    fn keeps_no_more_than_asked() {
+        let roll = L5rRoll::new(3, 2, 2);
+        assert_eq!(roll.rolled.len(), 5);
+        assert_eq!(roll.kept.len(), 2);
+
+        assert!(L5rRoll::new(3, 2, 0).kept.is_empty());
+    }
+}
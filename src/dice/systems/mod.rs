@@ -0,0 +1,13 @@
+pub mod bitd;
+pub mod coc;
+pub mod exalted;
+pub mod fate;
+pub mod l5r;
+pub mod mtg;
+pub mod ore;
+pub mod pbta;
+pub mod shadowrun;
+pub mod swade;
+pub mod v5;
+pub mod wod;
+pub mod yze;
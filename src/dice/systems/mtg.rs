@@ -0,0 +1,45 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanarFace {
+    Planeswalk,
+    Chaos,
+    Blank,
+}
+
+impl fmt::Display for PlanarFace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlanarFace::Planeswalk => write!(f, "Planeswalk! 🌀"),
+            PlanarFace::Chaos => write!(f, "Chaos! 💥"),
+            PlanarFace::Blank => write!(f, "Nothing happens. ⬜"),
+        }
+    }
+}
+
+// The Planechase die is a d6 with one planeswalker symbol, one chaos symbol and four blank faces
+pub const PLANAR_DIE: [PlanarFace; 6] = [
+    PlanarFace::Planeswalk,
+    PlanarFace::Chaos,
+    PlanarFace::Blank,
+    PlanarFace::Blank,
+    PlanarFace::Blank,
+    PlanarFace::Blank,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoinFace {
+    Heads,
+    Tails,
+}
+
+impl fmt::Display for CoinFace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoinFace::Heads => write!(f, "Heads"),
+            CoinFace::Tails => write!(f, "Tails"),
+        }
+    }
+}
+
+pub const COIN: [CoinFace; 2] = [CoinFace::Heads, CoinFace::Tails];
@@ -0,0 +1,110 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+};
+
+use crate::dice::pool::{
+    MAX_DICE,
+    Pool,
+};
+
+pub const DEFAULT_SIDES: u32 = 10;
+
+// A set of matching dice, written width x height: `3x7` is three dice showing 7
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchSet {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl fmt::Display for MatchSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+// One Roll Engine roll: the dice are grouped by face, and every face that came up more than once is a set
+#[derive(Debug, Clone, PartialEq)]
+pub struct OreRoll {
+    pub dice: Pool,
+}
+
+impl OreRoll {
+    pub fn new(count: u32) -> OreRoll {
+        OreRoll { dice: Pool::rolled(count.min(MAX_DICE), DEFAULT_SIDES) }
+    }
+
+    // For other kinds of dice, like `4d6`
+    pub fn from_pool(dice: Pool) -> OreRoll {
+        OreRoll { dice }
+    }
+
+    fn faces(&self) -> BTreeMap<u32, u32> {
+        let mut faces = BTreeMap::new();
+        for die in self.dice.dice() {
+            *faces.entry(die.result).or_insert(0) += 1;
+        }
+
+        faces
+    }
+
+    // Widest sets first, and the tallest first among sets of the same width
+    pub fn sets(&self) -> Vec<MatchSet> {
+        let mut sets = self.faces()
+            .into_iter()
+            .filter(|(_, width)| *width > 1)
+            .map(|(height, width)| MatchSet { width, height })
+            .collect::<Vec<MatchSet>>();
+        sets.sort_by_key(|set| std::cmp::Reverse((set.width, set.height)));
+
+        sets
+    }
+
+    // The set that usually decides the roll, like how fast an action goes
+    pub fn widest(&self) -> Option<MatchSet> {
+        self.sets().first().copied()
+    }
+
+    // Dice that didn't match anything, from highest to lowest
+    pub fn loose(&self) -> Vec<u32> {
+        self.faces()
+            .into_iter()
+            .rev()
+            .filter(|(_, count)| *count == 1)
+            .map(|(face, _)| face)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ore(results: &[u32]) -> OreRoll {
+        OreRoll::from_pool(Pool::from_results(DEFAULT_SIDES, results))
+    }
+
+    #[test]
+    fn sets_are_widest_then_tallest() {
+        let roll = ore(&[7, 2, 7, 9, 2, 7, 9, 1]);
+        let sets = roll.sets().iter().map(MatchSet::to_string).collect::<Vec<String>>();
+
+        assert_eq!(sets, vec!["3x7", "2x9", "2x2"]);
+        assert_eq!(roll.widest(), Some(MatchSet { width: 3, height: 7 }));
+        assert_eq!(roll.loose(), vec![1]);
+    }
+
+    #[test]
+    fn no_matches_leaves_every_die_loose() {
+        let roll = ore(&[3, 8, 5]);
+
+        assert!(roll.sets().is_empty());
+        assert_eq!(roll.widest(), None);
+        assert_eq!(roll.loose(), vec![8, 5, 3]);
+    }
+
+    #[test]
+    fn pools_are_capped() {
+        assert_eq!(OreRoll::new(u32::MAX).dice.dice().len(), MAX_DICE as usize);
+    }
+}
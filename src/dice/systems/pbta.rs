@@ -0,0 +1,116 @@
+use std::fmt;
+
+use crate::dice::pool::Pool;
+
+pub const STRONG_HIT: i64 = 10;
+pub const WEAK_HIT: i64 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PbtaResult {
+    StrongHit,
+    WeakHit,
+    Miss,
+}
+
+impl fmt::Display for PbtaResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            PbtaResult::StrongHit => "Strong hit",
+            PbtaResult::WeakHit => "Weak hit",
+            PbtaResult::Miss => "Miss",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Powered by the Apocalypse move: 2d6 plus a modifier. Advantage rolls 3d6 and keeps the highest two,
+// disadvantage keeps the lowest two.
+#[derive(Debug, Clone)]
+pub struct PbtaRoll {
+    pub dice: Pool,
+    pub kept: Pool,
+    pub modifier: i64,
+}
+
+impl PbtaRoll {
+    // Positive for advantage, negative for disadvantage
+    pub fn new(modifier: i64, advantage: i32) -> PbtaRoll {
+        let (dice, kept) = match advantage.signum() {
+            1 => {
+                let dice = Pool::rolled(3, 6);
+                let kept = dice.keep_highest(2);
+                (dice, kept)
+            },
+            -1 => {
+                let dice = Pool::rolled(3, 6);
+                let kept = dice.keep_lowest(2);
+                (dice, kept)
+            },
+            _ => {
+                let dice = Pool::rolled(2, 6);
+                (dice.clone(), dice)
+            },
+        };
+
+        PbtaRoll { dice, kept, modifier }
+    }
+
+    pub fn total(&self) -> i64 {
+        i64::from(self.kept.total()).saturating_add(self.modifier)
+    }
+
+    pub fn result(&self) -> PbtaResult {
+        match self.total() {
+            total if total >= STRONG_HIT => PbtaResult::StrongHit,
+            total if total >= WEAK_HIT => PbtaResult::WeakHit,
+            _ => PbtaResult::Miss,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn totals_fall_into_three_tiers() {
+        let cases = [
+            ([5, 5], 0, PbtaResult::StrongHit),
+            ([4, 4], 2, PbtaResult::StrongHit),
+            ([3, 4], 0, PbtaResult::WeakHit),
+            ([4, 5], 0, PbtaResult::WeakHit),
+            ([3, 3], 0, PbtaResult::Miss),
+            ([6, 6], -6, PbtaResult::Miss),
+        ];
+
+        for (results, modifier, expected) in cases.iter() {
+            let kept = Pool::from_results(6, results);
+            let roll = PbtaRoll { dice: kept.clone(), kept, modifier: *modifier };
+            assert_eq!(roll.result(), *expected, "{:?} {:+}", results, modifier);
+        }
+    }
+
+    #[test]
+    fn huge_modifiers_saturate() {
+        let sixes = Pool::from_results(6, &[6, 6]);
+        let roll = PbtaRoll { dice: sixes.clone(), kept: sixes, modifier: i64::MAX };
+        assert_eq!(roll.total(), i64::MAX);
+
+        let roll = PbtaRoll { modifier: i64::MIN, ..roll };
+        assert_eq!(roll.result(), PbtaResult::Miss);
+    }
+
+    #[test]
+    fn advantage_and_disadvantage_keep_two_of_three() {
+        let advantage = PbtaRoll::new(0, 1);
+        assert_eq!((advantage.dice.dice().len(), advantage.kept.dice().len()), (3, 2));
+        let lowest = advantage.dice.dice().iter().map(|die| die.result).min().unwrap();
+        assert_eq!(advantage.kept.total() + lowest, advantage.dice.total());
+
+        let disadvantage = PbtaRoll::new(0, -2);
+        let highest = disadvantage.dice.dice().iter().map(|die| die.result).max().unwrap();
+        assert_eq!(disadvantage.kept.total() + highest, disadvantage.dice.total());
+
+        assert_eq!(PbtaRoll::new(0, 0).dice.dice().len(), 2);
+    }
+}
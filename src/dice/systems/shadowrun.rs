@@ -0,0 +1,84 @@
+use crate::dice::pool::Pool;
+
+pub const HIT_TARGET: u32 = 5;
+
+// Shadowrun roll: a pool of d6s where every 5 or 6 is a hit
+#[derive(Debug, Clone)]
+pub struct ShadowrunRoll {
+    pub dice: Pool,
+    // Extra dice from exploding 6s when Edge is used
+    pub edge: Pool,
+    pub limit: Option<u32>,
+    pub used_edge: bool,
+    raw_hits: u32,
+}
+
+impl ShadowrunRoll {
+    pub fn new(pool_size: u32, limit: Option<u32>, used_edge: bool) -> ShadowrunRoll {
+        let mut builder = Pool::builder().dice(pool_size, 6).target(HIT_TARGET);
+        if used_edge { builder = builder.explode_on(6); }
+        let roll = builder.build();
+        let raw_hits = roll.successes();
+
+        ShadowrunRoll { dice: roll.dice, edge: roll.exploded, limit, used_edge, raw_hits }
+    }
+
+    pub fn raw_hits(&self) -> u32 {
+        self.raw_hits
+    }
+
+    // Using Edge pushes the limit, so the limit only caps hits without it
+    pub fn hits(&self) -> u32 {
+        match (self.limit, self.used_edge) {
+            (Some(limit), false) => self.raw_hits().min(limit),
+            _ => self.raw_hits(),
+        }
+    }
+
+    // Glitches only look at the original pool, not dice added by Edge
+    pub fn is_glitch(&self) -> bool {
+        !self.dice.is_empty() && self.dice.count_exactly(1) * 2 >= self.dice.dice().len() as u32
+    }
+
+    pub fn is_critical_glitch(&self) -> bool {
+        self.is_glitch() && self.raw_hits() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shadowrun(dice: &[u32], limit: Option<u32>, used_edge: bool) -> ShadowrunRoll {
+        let dice = Pool::from_results(6, dice);
+        let raw_hits = dice.count_at_least(HIT_TARGET);
+
+        ShadowrunRoll { dice, edge: Pool::default(), limit, used_edge, raw_hits }
+    }
+
+    #[test]
+    fn fives_and_sixes_hit_up_to_the_limit() {
+        assert_eq!(shadowrun(&[5, 6, 4, 6], None, false).hits(), 3);
+        assert_eq!(shadowrun(&[5, 6, 4, 6], Some(2), false).hits(), 2);
+        assert_eq!(shadowrun(&[5, 6, 4, 6], Some(2), true).hits(), 3);
+    }
+
+    #[test]
+    fn half_the_dice_on_ones_is_a_glitch() {
+        let glitch = shadowrun(&[1, 1, 5, 3], None, false);
+        assert!(glitch.is_glitch() && !glitch.is_critical_glitch());
+
+        assert!(shadowrun(&[1, 1, 2, 3], None, false).is_critical_glitch());
+        assert!(!shadowrun(&[1, 2, 3], None, false).is_glitch());
+        assert!(!shadowrun(&[], None, false).is_glitch());
+    }
+
+    #[test]
+    fn edge_explodes_sixes_into_extra_hits() {
+        let roll = ShadowrunRoll::new(20, Some(1), true);
+        assert_eq!(roll.raw_hits(), roll.dice.count_at_least(HIT_TARGET) + roll.edge.count_at_least(HIT_TARGET));
+        assert_eq!(roll.hits(), roll.raw_hits());
+
+        assert!(ShadowrunRoll::new(20, None, false).edge.is_empty());
+    }
+}
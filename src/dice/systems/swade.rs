@@ -0,0 +1,115 @@
+use crate::dice::pool::Pool;
+
+pub const TRAIT_DICE: [u32; 5] = [4, 6, 8, 10, 12];
+pub const WILD_DIE_SIDES: u32 = 6;
+pub const DEFAULT_TARGET: i64 = 4;
+pub const RAISE_STEP: i64 = 4;
+
+// A die that rolls again and adds on its highest face, which Savage Worlds calls acing
+fn roll_acing(sides: u32) -> Pool {
+    let first = Pool::rolled(1, sides);
+    let aces = first.explode(sides);
+
+    first.merge(aces)
+}
+
+// Savage Worlds roll: a trait die, plus a d6 wild die for Wild Cards, both acing, keeping the higher
+#[derive(Debug, Clone)]
+pub struct SwadeRoll {
+    pub trait_die: Pool,
+    pub wild_die: Option<Pool>,
+    pub modifier: i64,
+    pub target: i64,
+}
+
+impl SwadeRoll {
+    pub fn new(trait_sides: u32, wild_card: bool, modifier: i64, target: i64) -> SwadeRoll {
+        SwadeRoll {
+            trait_die: roll_acing(trait_sides),
+            wild_die: if wild_card { Some(roll_acing(WILD_DIE_SIDES)) } else { None },
+            modifier,
+            target,
+        }
+    }
+
+    pub fn wild_die_kept(&self) -> bool {
+        self.wild_die.as_ref().map(|wild| wild.total() > self.trait_die.total()).unwrap_or(false)
+    }
+
+    pub fn total(&self) -> i64 {
+        let kept = match &self.wild_die {
+            Some(wild) if self.wild_die_kept() => wild.total(),
+            _ => self.trait_die.total(),
+        };
+
+        i64::from(kept).saturating_add(self.modifier)
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.total() >= self.target
+    }
+
+    // Every full 4 over the target number is a raise
+    pub fn raises(&self) -> i64 {
+        if self.is_success() { self.total().saturating_sub(self.target) / RAISE_STEP } else { 0 }
+    }
+
+    // Snake eyes: both the trait die and the wild die come up 1
+    pub fn is_critical_failure(&self) -> bool {
+        let first_is_one = |pool: &Pool| pool.dice().first().map(|die| die.result == 1).unwrap_or(false);
+
+        match &self.wild_die {
+            Some(wild) => first_is_one(&self.trait_die) && first_is_one(wild),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An aced die shows up as every face it rolled, so [8, 3] is a d8 that aced once for 11
+    fn swade(trait_die: &[u32], wild_die: Option<&[u32]>, modifier: i64) -> SwadeRoll {
+        SwadeRoll {
+            trait_die: Pool::from_results(8, trait_die),
+            wild_die: wild_die.map(|results| Pool::from_results(WILD_DIE_SIDES, results)),
+            modifier,
+            target: DEFAULT_TARGET,
+        }
+    }
+
+    #[test]
+    fn the_higher_die_is_kept() {
+        let roll = swade(&[3], Some(&[6, 2]), 0);
+        assert!(roll.wild_die_kept());
+        assert_eq!(roll.total(), 8);
+
+        let roll = swade(&[8, 3], Some(&[5]), -1);
+        assert!(!roll.wild_die_kept());
+        assert_eq!(roll.total(), 10);
+
+        assert!(!swade(&[2], None, 0).wild_die_kept());
+    }
+
+    #[test]
+    fn every_four_over_the_target_is_a_raise() {
+        assert_eq!(swade(&[3], None, 0).raises(), 0);
+        assert_eq!(swade(&[7], None, 0).raises(), 0);
+        assert_eq!(swade(&[8], None, 0).raises(), 1);
+        assert_eq!(swade(&[8, 8, 4], None, 0).raises(), 4);
+    }
+
+    #[test]
+    fn snake_eyes_needs_a_wild_die() {
+        assert!(swade(&[1], Some(&[1]), 5).is_critical_failure());
+        assert!(!swade(&[1], Some(&[2]), 0).is_critical_failure());
+        assert!(!swade(&[1], None, 0).is_critical_failure());
+    }
+
+    #[test]
+    fn huge_modifiers_saturate() {
+        assert_eq!(swade(&[8], None, i64::MAX).total(), i64::MAX);
+        assert_eq!(swade(&[8], None, i64::MIN).raises(), 0);
+    }
+}
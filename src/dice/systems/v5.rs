@@ -0,0 +1,116 @@
+use crate::dice::pool::{
+    MAX_DICE,
+    Pool,
+};
+
+pub const SUCCESS_TARGET: u32 = 6;
+pub const MAX_HUNGER: u32 = 5;
+
+// Vampire: the Masquerade 5e roll: a pool of d10s where every 6+ is a success, some of which are hunger dice
+#[derive(Debug, Clone)]
+pub struct V5Roll {
+    pub dice: Pool,
+    pub hunger: Pool,
+    // Successes needed to pass, if the roll has a difficulty
+    pub difficulty: Option<u32>,
+}
+
+impl V5Roll {
+    // Hunger dice replace regular dice rather than adding to the pool, and a pool is never smaller than its hunger
+    pub fn new(pool_size: u32, hunger: u32, difficulty: Option<u32>) -> V5Roll {
+        let hunger = hunger.min(MAX_HUNGER);
+        let pool_size = pool_size.max(hunger).min(MAX_DICE);
+
+        V5Roll {
+            dice: Pool::rolled(pool_size - hunger, 10),
+            hunger: Pool::rolled(hunger, 10),
+            difficulty,
+        }
+    }
+
+    fn tens(&self) -> u32 {
+        self.dice.count_exactly(10) + self.hunger.count_exactly(10)
+    }
+
+    // Every pair of 10s is a critical, worth four successes instead of two
+    pub fn successes(&self) -> u32 {
+        self.dice.count_at_least(SUCCESS_TARGET) + self.hunger.count_at_least(SUCCESS_TARGET) + self.tens() / 2 * 2
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.successes() >= self.difficulty.unwrap_or(1).max(1)
+    }
+
+    pub fn is_critical(&self) -> bool {
+        self.is_success() && self.tens() >= 2
+    }
+
+    // A critical with a 10 on any hunger die
+    pub fn is_messy_critical(&self) -> bool {
+        self.is_critical() && self.hunger.count_exactly(10) > 0
+    }
+
+    // A failure with a 1 on any hunger die
+    pub fn is_bestial_failure(&self) -> bool {
+        !self.is_success() && self.hunger.count_exactly(1) > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v5(dice: &[u32], hunger: &[u32]) -> V5Roll {
+        V5Roll { dice: Pool::from_results(10, dice), hunger: Pool::from_results(10, hunger), difficulty: None }
+    }
+
+    #[test]
+    fn pairs_of_tens_are_worth_four() {
+        assert_eq!(v5(&[6, 3, 9], &[]).successes(), 2);
+        assert_eq!(v5(&[10, 10, 6, 3], &[]).successes(), 5);
+        assert_eq!(v5(&[10, 10, 10], &[]).successes(), 5);
+        assert_eq!(v5(&[10], &[10, 2]).successes(), 4);
+    }
+
+    #[test]
+    fn hunger_tens_make_a_critical_messy() {
+        let clean = v5(&[10, 10], &[4]);
+        assert!(clean.is_critical() && !clean.is_messy_critical());
+
+        let messy = v5(&[10, 5], &[10]);
+        assert!(messy.is_messy_critical());
+
+        assert!(!v5(&[5], &[10]).is_critical());
+    }
+
+    #[test]
+    fn hunger_ones_make_a_failure_bestial() {
+        assert!(v5(&[3], &[1]).is_bestial_failure());
+        assert!(!v5(&[3], &[2]).is_bestial_failure());
+        assert!(!v5(&[6], &[1]).is_bestial_failure());
+    }
+
+    #[test]
+    fn difficulty_sets_the_successes_needed() {
+        let roll = V5Roll { difficulty: Some(3), ..v5(&[6, 7], &[1]) };
+        assert!(!roll.is_success() && roll.is_bestial_failure());
+
+        let roll = V5Roll { difficulty: Some(5), ..v5(&[10, 10], &[]) };
+        assert!(!roll.is_success() && !roll.is_critical());
+
+        assert!(V5Roll { difficulty: Some(0), ..v5(&[6], &[]) }.is_success());
+        assert!(!V5Roll { difficulty: Some(0), ..v5(&[2], &[]) }.is_success());
+    }
+
+    #[test]
+    fn hunger_replaces_pool_dice() {
+        let roll = V5Roll::new(5, 2, None);
+        assert_eq!((roll.dice.dice().len(), roll.hunger.dice().len()), (3, 2));
+
+        let roll = V5Roll::new(1, 3, None);
+        assert_eq!((roll.dice.dice().len(), roll.hunger.dice().len()), (0, 3));
+
+        let roll = V5Roll::new(10, 9, None);
+        assert_eq!((roll.dice.dice().len(), roll.hunger.dice().len()), (5, MAX_HUNGER as usize));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::dice::pool::{
+    MAX_DICE,
+    Pool,
+};
+
+pub const SUCCESS_TARGET: u32 = 8;
+pub const EXCEPTIONAL_SUCCESSES: u32 = 5;
+
+// Chronicles of Darkness roll: a pool of d10s where every 8+ is a success
+#[derive(Debug, Clone)]
+pub struct WodRoll {
+    // With rote quality, failed dice are rerolled once in place
+    pub dice: Pool,
+    pub rote: bool,
+    // Extra dice from the n-again rule
+    pub again: Pool,
+    pub again_threshold: Option<u32>,
+    // A pool reduced to zero or less rolls a single chance die instead
+    pub chance_die: bool,
+}
+
+impl WodRoll {
+    pub fn new(pool_size: i64, again_threshold: Option<u32>, rote: bool) -> WodRoll {
+        if pool_size <= 0 {
+            return WodRoll {
+                dice: Pool::rolled(1, 10),
+                rote: false,
+                again: Pool::default(),
+                again_threshold: None,
+                chance_die: true,
+            };
+        }
+
+        let mut dice = Pool::rolled((pool_size as u64).min(u64::from(MAX_DICE)) as u32, 10);
+        if rote { dice = dice.reroll_below(SUCCESS_TARGET); }
+        let again = match again_threshold {
+            Some(threshold) => dice.explode(threshold),
+            None => Pool::default(),
+        };
+
+        WodRoll { dice, rote, again, again_threshold, chance_die: false }
+    }
+
+    pub fn successes(&self) -> u32 {
+        if self.chance_die {
+            return self.dice.count_exactly(10);
+        }
+
+        self.dice.count_at_least(SUCCESS_TARGET) + self.again.count_at_least(SUCCESS_TARGET)
+    }
+
+    pub fn is_exceptional(&self) -> bool {
+        self.successes() >= EXCEPTIONAL_SUCCESSES
+    }
+
+    pub fn is_dramatic_failure(&self) -> bool {
+        self.chance_die && self.dice.count_exactly(1) > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn again_dice_add_successes() {
+        let mut roll = WodRoll {
+            dice: Pool::from_results(10, &[8, 10, 3]),
+            rote: false,
+            again: Pool::from_results(10, &[2]),
+            again_threshold: Some(10),
+            chance_die: false,
+        };
+        assert_eq!(roll.successes(), 2);
+        assert!(!roll.is_exceptional());
+
+        roll.again = Pool::from_results(10, &[10, 9, 8]);
+        assert_eq!(roll.successes(), 5);
+        assert!(roll.is_exceptional());
+    }
+
+    #[test]
+    fn chance_dice_only_succeed_on_ten() {
+        let chance = |result| WodRoll {
+            dice: Pool::from_results(10, &[result]),
+            rote: false,
+            again: Pool::default(),
+            again_threshold: None,
+            chance_die: true,
+        };
+
+        assert_eq!(chance(10).successes(), 1);
+        assert_eq!(chance(8).successes(), 0);
+        assert!(chance(1).is_dramatic_failure());
+        assert!(!chance(2).is_dramatic_failure());
+    }
+
+    #[test]
+    fn pools_of_zero_or_less_roll_a_chance_die() {
+        for size in [0, -3, i64::MIN] {
+            let roll = WodRoll::new(size, Some(10), true);
+            assert!(roll.chance_die && !roll.rote && roll.again.is_empty());
+            assert_eq!(roll.dice.dice().len(), 1);
+        }
+        assert_eq!(WodRoll::new(i64::MAX, None, false).dice.dice().len(), MAX_DICE as usize);
+    }
+
+    #[test]
+    fn rote_rerolls_each_failed_die_once() {
+        let roll = WodRoll::new(50, None, true);
+
+        for die in roll.dice.dice() {
+            match die.previous.as_slice() {
+                [] => assert!(die.result >= SUCCESS_TARGET),
+                [previous] => assert!(*previous < SUCCESS_TARGET),
+                history => panic!("rerolled more than once: {:?}", history),
+            }
+        }
+    }
+}
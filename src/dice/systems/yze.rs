@@ -0,0 +1,106 @@
+use crate::dice::pool::{
+    MAX_DICE,
+    Pool,
+};
+
+pub const SUCCESS_FACE: u32 = 6;
+pub const BANE_FACE: u32 = 1;
+
+// Year Zero Engine roll (Mutant: Year Zero, Forbidden Lands): separate d6 pools for attribute, skill and gear, where
+// every 6 is a success and 1s on attribute and gear dice are banes
+#[derive(Debug, Clone)]
+pub struct YzeRoll {
+    pub attribute: Pool,
+    pub skill: Pool,
+    pub gear: Pool,
+    pub pushed: bool,
+}
+
+impl YzeRoll {
+    pub fn new(attribute: u32, skill: u32, gear: u32) -> YzeRoll {
+        let attribute = attribute.min(MAX_DICE);
+        let skill = skill.min(MAX_DICE - attribute);
+        let gear = gear.min(MAX_DICE - attribute - skill);
+
+        YzeRoll {
+            attribute: Pool::rolled(attribute, 6),
+            skill: Pool::rolled(skill, 6),
+            gear: Pool::rolled(gear, 6),
+            pushed: false,
+        }
+    }
+
+    // Pushing rerolls every die that isn't a success or a bane, and a roll can only be pushed once
+    pub fn push(&self) -> YzeRoll {
+        let keep = [SUCCESS_FACE, BANE_FACE];
+
+        YzeRoll {
+            attribute: self.attribute.reroll_except(&keep),
+            skill: self.skill.reroll_except(&keep),
+            gear: self.gear.reroll_except(&keep),
+            pushed: true,
+        }
+    }
+
+    pub fn successes(&self) -> u32 {
+        self.attribute.count_exactly(SUCCESS_FACE) + self.skill.count_exactly(SUCCESS_FACE) + self.gear.count_exactly(SUCCESS_FACE)
+    }
+
+    // Attribute banes are damage to the character after a push
+    pub fn attribute_banes(&self) -> u32 {
+        self.attribute.count_exactly(BANE_FACE)
+    }
+
+    // Gear banes reduce the gear bonus after a push
+    pub fn gear_banes(&self) -> u32 {
+        self.gear.count_exactly(BANE_FACE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yze(attribute: &[u32], skill: &[u32], gear: &[u32]) -> YzeRoll {
+        YzeRoll {
+            attribute: Pool::from_results(6, attribute),
+            skill: Pool::from_results(6, skill),
+            gear: Pool::from_results(6, gear),
+            pushed: false,
+        }
+    }
+
+    #[test]
+    fn sixes_succeed_in_every_pool() {
+        let roll = yze(&[6, 3, 1], &[6, 6], &[2, 6]);
+        assert_eq!(roll.successes(), 4);
+    }
+
+    // Skill dice have no banes, so a 1 on one is only a failed die
+    #[test]
+    fn ones_are_banes_on_attribute_and_gear_only() {
+        let roll = yze(&[1, 1, 4], &[1, 1], &[1]);
+        assert_eq!(roll.attribute_banes(), 2);
+        assert_eq!(roll.gear_banes(), 1);
+    }
+
+    #[test]
+    fn pushing_keeps_sixes_and_ones() {
+        let pushed = yze(&[6, 1, 3], &[1, 4], &[5, 6]).push();
+        assert!(pushed.pushed);
+
+        let results = |pool: &Pool| pool.dice().iter().map(|die| die.result).collect::<Vec<u32>>();
+        assert_eq!(results(&pushed.attribute)[..2], [6, 1]);
+        assert_eq!(results(&pushed.skill)[0], 1);
+        assert_eq!(results(&pushed.gear)[1], 6);
+        assert_eq!(pushed.attribute.dice()[2].previous, vec![3]);
+        assert!(pushed.attribute.dice()[0].previous.is_empty());
+    }
+
+    #[test]
+    fn pools_share_the_dice_cap() {
+        let roll = YzeRoll::new(MAX_DICE, 5, 5);
+        assert_eq!(roll.attribute.dice().len(), MAX_DICE as usize);
+        assert!(roll.skill.is_empty() && roll.gear.is_empty());
+    }
+}
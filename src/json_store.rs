@@ -0,0 +1,42 @@
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use std::{
+    io,
+    path::Path,
+};
+use tokio::fs;
+
+// Reads a saved registry. A missing file is an empty registry. A file that isn't valid JSON is moved aside to
+// `<path>.corrupt` so it can be looked at later, and the bot starts with an empty registry instead of refusing to
+// start at all.
+pub async fn load<T: DeserializeOwned + Default>(path: &str) -> io::Result<T> {
+    if !Path::new(path).exists() {
+        return Ok(T::default());
+    }
+
+    let data = fs::read_to_string(path).await?;
+    match serde_json::from_str(&data) {
+        Ok(value) => Ok(value),
+        Err(why) => {
+            let corrupt_path = format!("{}.corrupt", path);
+            fs::rename(path, &corrupt_path).await?;
+            println!("Could not read {}, moved it to {} and started fresh: {}", path, corrupt_path, why);
+
+            Ok(T::default())
+        },
+    }
+}
+
+// Writes to a temporary file first and renames it over the old one, so a crash mid-write can't leave half a file
+pub async fn save<T: Serialize>(path: &str, value: &T) -> io::Result<()> {
+    if let Some(folder) = Path::new(path).parent() {
+        fs::create_dir_all(folder).await?;
+    }
+    let data = serde_json::to_string_pretty(value).map_err(|why| io::Error::new(io::ErrorKind::InvalidData, why))?;
+
+    let temp_path = format!("{}.tmp", path);
+    fs::write(&temp_path, data).await?;
+    fs::rename(&temp_path, path).await
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{
+    GuildId,
+    UserId,
+};
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    io,
+};
+
+use crate::json_store;
+
+// Saved roll expressions by name, per user per guild. DMs are stored under guild 0.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct MacroRegistry {
+    #[serde(skip)]
+    path: String,
+    macros: HashMap<u64, HashMap<u64, BTreeMap<String, String>>>,
+}
+
+impl MacroRegistry {
+    pub async fn load(path: &str) -> io::Result<MacroRegistry> {
+        let mut registry: MacroRegistry = json_store::load(path).await?;
+        registry.path = path.to_string();
+
+        Ok(registry)
+    }
+
+    pub fn get(&self, guild: Option<GuildId>, user: UserId, name: &str) -> Option<&String> {
+        self.list(guild, user)?.get(&name.to_lowercase())
+    }
+
+    pub fn list(&self, guild: Option<GuildId>, user: UserId) -> Option<&BTreeMap<String, String>> {
+        self.macros.get(&guild_key(guild))?.get(&user.0)
+    }
+
+    pub async fn save(&mut self, guild: Option<GuildId>, user: UserId, name: &str, expression: &str) -> io::Result<()> {
+        self.macros
+            .entry(guild_key(guild))
+            .or_default()
+            .entry(user.0)
+            .or_default()
+            .insert(name.to_lowercase(), expression.to_string());
+
+        self.persist().await
+    }
+
+    // Returns whether there was a macro to remove
+    pub async fn remove(&mut self, guild: Option<GuildId>, user: UserId, name: &str) -> io::Result<bool> {
+        let removed = self.macros
+            .get_mut(&guild_key(guild))
+            .and_then(|users| users.get_mut(&user.0))
+            .and_then(|user_macros| user_macros.remove(&name.to_lowercase()))
+            .is_some();
+
+        if removed { self.persist().await?; }
+
+        Ok(removed)
+    }
+
+    async fn persist(&self) -> io::Result<()> {
+        json_store::save(&self.path, self).await
+    }
+}
+
+fn guild_key(guild: Option<GuildId>) -> u64 {
+    guild.map(|guild| guild.0).unwrap_or(0)
+}
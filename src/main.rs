@@ -1,6 +1,7 @@
 use std::{
     collections::HashSet,
     sync::Arc,
+    time::Duration,
 };
 
 use serenity::{
@@ -21,20 +22,51 @@ use serenity::{
     prelude::*,
 };
 
+mod aliases;
+use aliases::AliasRegistry;
+
 mod config;
 use config::Config;
 
+mod dice;
+
+mod json_store;
+
+mod macros;
+use macros::MacroRegistry;
+
+mod mtg;
+use mtg::MtgTables;
+
+mod usage;
+use usage::UsageRegistry;
+
+mod settings;
+use settings::SettingsRegistry;
+
+mod roll_observers;
+use roll_observers::RollObservers;
+
+mod tray;
+use tray::YzeTray;
+
 mod messaging;
 use messaging::{
+    alias_framework::AliasFramework,
+    cleanup::RepliesMap,
     message_handler::Handler,
 };
 
 mod commands;
 use commands::{
+    aliases::*,
     funsies::*,
     general::*,
     logging::*,
+    macros::*,
+    mtg::*,
     rolling::*,
+    settings::*,
 };
 
 struct LogsKey;
@@ -43,6 +75,69 @@ impl TypeMapKey for LogsKey {
     type Value = Arc<Mutex<commands::logging::LogsMap>>;
 }
 
+struct RepliesKey;
+
+impl TypeMapKey for RepliesKey {
+    type Value = Arc<Mutex<RepliesMap>>;
+}
+
+struct MacrosKey;
+
+impl TypeMapKey for MacrosKey {
+    type Value = Arc<Mutex<MacroRegistry>>;
+}
+
+struct AliasesKey;
+
+impl TypeMapKey for AliasesKey {
+    type Value = Arc<Mutex<AliasRegistry>>;
+}
+
+struct SettingsKey;
+
+impl TypeMapKey for SettingsKey {
+    type Value = Arc<Mutex<SettingsRegistry>>;
+}
+
+struct MtgKey;
+
+impl TypeMapKey for MtgKey {
+    type Value = Arc<Mutex<MtgTables>>;
+}
+
+// Every name and alias of the registered commands, for suggesting them when someone makes a typo and for keeping
+// guild aliases from taking their names
+struct CommandNamesKey;
+
+impl TypeMapKey for CommandNamesKey {
+    type Value = Vec<String>;
+}
+
+struct UsageKey;
+
+impl TypeMapKey for UsageKey {
+    type Value = Arc<Mutex<UsageRegistry>>;
+}
+
+struct YzeTrayKey;
+
+impl TypeMapKey for YzeTrayKey {
+    type Value = Arc<Mutex<YzeTray>>;
+}
+
+// Face counts from every roll so far, for !fairness
+struct FaceTallyKey;
+
+impl TypeMapKey for FaceTallyKey {
+    type Value = Arc<Mutex<dice::fairness::FaceTally>>;
+}
+
+struct RollObserversKey;
+
+impl TypeMapKey for RollObserversKey {
+    type Value = Arc<RollObservers>;
+}
+
 struct ConfigKey;
 
 impl TypeMapKey for ConfigKey {
@@ -51,21 +146,48 @@ impl TypeMapKey for ConfigKey {
 
 #[group]
 #[description = "General commands related to bot operation."]
-#[commands(bye, hello, pfp, ping)]
+#[commands(bye, hello, pfp, ping, usage)]
 struct General;
 
 #[group]
 #[description = "Miscellaneous call and response commands for fun.\n\n
 Feel free to try them out, but don't spam! ❤"]
+#[checks(funsies_allowed)]
 #[commands(atom, shadow, squid, unyu, yuru)]
 struct Funsies;
 
+#[group]
+#[description = "Commands for changing how I behave in this server. Servers only (not available in DMs)!\n\n
+These need the Manage Server permission. Use !funsiesconfig to turn the funsies commands off or keep them to some channels, !gmrole to choose who gets a copy of secret rolls, and !usagetracking to stop counting command usage."]
+#[only_in(guilds)]
+#[commands(funsiesconfig, gmrole, usagetracking)]
+struct Settings;
+
 #[group]
 #[description = "Commands related to rolling dice.\n\n
 Use !roll for generic dice rolls or one of the specialized functions to use simplified syntax tailored to the system."]
-#[commands(roll, exroll, l5r, sroll, wod)]
+#[commands(roll, bitd, chance, coc, exroll, fairness, fate, gmroll, haggle, l5r, ore, pbta, sroll, swade, v5, weighted, wod, yze)]
 struct Roll;
 
+#[group]
+#[description = "Commands for Magic: the Gathering games.\n\n
+Use !planar for the Planechase die and !mtgflip to flip coins. In servers, !life keeps track of life totals and commander damage for each channel's game, and !monarch and !initiative keep track of who holds them."]
+#[commands(planar, mtgflip, life, monarch, initiative)]
+struct Mtg;
+
+#[group]
+#[description = "Commands for saving rolls to use later.\n\n
+Use !save to save a roll under a name, then roll it with !roll and the name. Your saved rolls are only yours, and each server has its own."]
+#[commands(save, unsave, macros)]
+struct Macros;
+
+#[group]
+#[description = "Commands for managing command aliases. Servers only (not available in DMs)!\n\n
+Use !alias to give a command a new name for this server, !unalias to remove one, and !aliases to see them all. Adding and removing aliases needs the Manage Server permission."]
+#[only_in(guilds)]
+#[commands(alias, unalias, aliases)]
+struct Aliases;
+
 #[group]
 #[description = "Commands for logging channels. Servers only (not available in DMs)!\n\n
 Use !log to start logging, !unlog to stop logging, and !logging to check whether I'm already logging.\n
@@ -97,26 +219,98 @@ async fn my_help(
 
 #[hook]
 async fn normal_message(ctx: &Context, msg: &Message) {
-    let mut log_data = ctx.data.write().await;
-    let log_map = log_data
-                    .get_mut::<LogsKey>()
-                    .expect("Failed to retrieve logs map!")
-                    .lock().await;
-
-    if let Some(log) = log_map.get(&msg.channel_id) {
-        match log.record(msg) {
-            Ok(_) => return,
-            Err(why) => println!("Error recording log message: {}", why)
+    // The data lock has to be released before rolling, which needs it again
+    let inline_rolls = {
+        let mut log_data = ctx.data.write().await;
+        let rolling = log_data.get::<ConfigKey>().expect("Failed to retrieve config!").features.rolling;
+        let log_map = log_data
+                        .get_mut::<LogsKey>()
+                        .expect("Failed to retrieve logs map!")
+                        .lock().await;
+
+        if let Some(log) = log_map.get(&msg.channel_id) {
+            if let Err(why) = log.record(msg) {
+                println!("Error recording log message: {}", why);
+            }
+        }
+
+        rolling
+    };
+
+    if inline_rolls {
+        if let Err(why) = messaging::inline_rolls::reply_inline_rolls(ctx, msg).await {
+            println!("Error replying to inline rolls: {:?}", why);
+        }
+    }
+}
+
+#[hook]
+async fn after(ctx: &Context, msg: &Message, command_name: &str, result: CommandResult) {
+    let data = ctx.data.read().await;
+    let opted_out = match msg.guild_id {
+        Some(guild_id) => {
+            let settings = data.get::<SettingsKey>().expect("Failed to retrieve guild settings!").lock().await;
+            settings.get(guild_id).usage_opt_out
+        },
+        None => false,
+    };
+    if opted_out { return; }
+
+    let mut usage = data.get::<UsageKey>().expect("Failed to retrieve usage registry!").lock().await;
+    usage.record(msg.guild_id, command_name, result.is_err());
+}
+
+#[hook]
+async fn unknown_command(ctx: &Context, msg: &Message, unknown_command_name: &str) {
+    let suggestion = {
+        let data = ctx.data.read().await;
+        let names = data.get::<CommandNamesKey>().expect("Failed to retrieve command names!");
+        let cfg = data.get::<ConfigKey>().expect("Failed to retrieve config!");
+
+        messaging::typos::closest(unknown_command_name, names).map(|name| (cfg.prefix.clone(), name.to_string()))
+    };
+
+    if let Some((prefix, name)) = suggestion {
+        let typo = format!("☢ I don't know `{}{}`! ☢\nDid you mean `{}{}`?", prefix, unknown_command_name, prefix, name);
+        if let Err(why) = msg.channel_id.say(&ctx.http, typo).await {
+            println!("Error suggesting command: {:?}", why);
         }
     }
-    return;
 }
 
 #[tokio::main]
 async fn main() {
-    let config = Config::new();
+    let config = match Config::new() {
+        Ok(config) => config,
+        Err(why) => panic!("Could not load config: {}", why),
+    };
+
+    let Config { discord_token, prefix, features, .. } = &config;
+
+    let macro_registry = match MacroRegistry::load(&config.macros.file_path).await {
+        Ok(registry) => registry,
+        Err(why) => panic!("Could not load saved macros: {}", why),
+    };
 
-    let Config { discord_token, prefix, log_folder_path: _, pfp_source: _} = &config;
+    let alias_registry = match AliasRegistry::load(&config.aliases.file_path).await {
+        Ok(registry) => registry,
+        Err(why) => panic!("Could not load aliases: {}", why),
+    };
+
+    let settings_registry = match SettingsRegistry::load(&config.settings.file_path).await {
+        Ok(registry) => registry,
+        Err(why) => panic!("Could not load guild settings: {}", why),
+    };
+
+    let mtg_tables = match MtgTables::load(&config.mtg.file_path).await {
+        Ok(tables) => tables,
+        Err(why) => panic!("Could not load MTG tables: {}", why),
+    };
+
+    let usage_registry = match UsageRegistry::load(&config.usage.file_path).await {
+        Ok(registry) => registry,
+        Err(why) => panic!("Could not load command usage: {}", why),
+    };
 
     let http = Http::new_with_token(discord_token);
 
@@ -130,7 +324,7 @@ async fn main() {
         Err(why) => panic!("Could not access application info: {:?}", why),
     };
 
-    let framework = StandardFramework::new()
+    let mut framework = StandardFramework::new()
         .configure(|c| c
             .owners(owners)
             .prefix(prefix)
@@ -138,16 +332,58 @@ async fn main() {
             .with_whitespace(true)
         )
         .normal_message(normal_message)
-        .help(&MY_HELP)
-        // .group(&ROLL_GROUP)
-        .group(&GENERAL_GROUP)
-        .group(&LOGGING_GROUP)
-        .group(&FUNSIES_GROUP);
-
-    let mut client = Client::builder(&discord_token)
-        .framework(framework)
+        .unrecognised_command(unknown_command)
+        .after(after)
+        .help(&MY_HELP);
+
+    // Groups that aren't registered don't respond and don't show up in help
+    let mut groups = vec![&GENERAL_GROUP, &SETTINGS_GROUP];
+    if features.rolling { groups.push(&ROLL_GROUP); }
+    if features.macros { groups.push(&MACROS_GROUP); }
+    if features.aliases { groups.push(&ALIASES_GROUP); }
+    if features.mtg { groups.push(&MTG_GROUP); }
+    if features.logging { groups.push(&LOGGING_GROUP); }
+    if features.funsies { groups.push(&FUNSIES_GROUP); }
+
+    let mut command_names = vec![MY_HELP.options.names[0].to_string()];
+    for group in groups {
+        framework.group_add(group);
+        for command in group.options.commands {
+            command_names.extend(command.options.names.iter().map(|name| name.to_string()));
+        }
+    }
+
+    let usage_registry = Arc::new(Mutex::new(usage_registry));
+    let flush_interval = Duration::from_secs(config.usage.flush_interval_secs);
+    let usage_to_flush = Arc::clone(&usage_registry);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(flush_interval);
+        loop {
+            interval.tick().await;
+            if let Err(why) = usage_to_flush.lock().await.flush().await {
+                println!("Error saving command usage: {}", why);
+            }
+        }
+    });
+
+    let face_tally = Arc::new(Mutex::new(dice::fairness::FaceTally::default()));
+    let mut roll_observers = RollObservers::default();
+    roll_observers.subscribe(face_tally.clone());
+
+    let mut client = Client::builder(discord_token)
+        .framework(AliasFramework::new(framework, prefix))
         .event_handler(Handler::new())
         .type_map_insert::<LogsKey>(Arc::new(Mutex::new(commands::logging::LogsMap::new())))
+        .type_map_insert::<AliasesKey>(Arc::new(Mutex::new(alias_registry)))
+        .type_map_insert::<UsageKey>(usage_registry)
+        .type_map_insert::<MtgKey>(Arc::new(Mutex::new(mtg_tables)))
+        .type_map_insert::<SettingsKey>(Arc::new(Mutex::new(settings_registry)))
+        .type_map_insert::<MacrosKey>(Arc::new(Mutex::new(macro_registry)))
+        .type_map_insert::<RepliesKey>(Arc::new(Mutex::new(RepliesMap::new())))
+        .type_map_insert::<YzeTrayKey>(Arc::new(Mutex::new(YzeTray::default())))
+        .type_map_insert::<FaceTallyKey>(face_tally)
+        .type_map_insert::<RollObserversKey>(Arc::new(roll_observers))
+        .type_map_insert::<CommandNamesKey>(command_names)
         .type_map_insert::<ConfigKey>(config)
         .await
         .expect("Error creating client");
@@ -0,0 +1,42 @@
+use serenity::{
+    async_trait,
+    framework::{
+        Framework,
+        StandardFramework,
+    },
+    model::channel::Message,
+    prelude::*,
+};
+
+// Wraps the standard framework so guild aliases are expanded before the message is dispatched,
+// since the standard framework only knows about the aliases compiled into the commands
+pub struct AliasFramework {
+    inner: StandardFramework,
+    prefix: String,
+}
+
+impl AliasFramework {
+    pub fn new(inner: StandardFramework, prefix: &str) -> AliasFramework {
+        AliasFramework { inner, prefix: prefix.to_string() }
+    }
+}
+
+#[async_trait]
+impl Framework for AliasFramework {
+    async fn dispatch(&self, ctx: Context, mut msg: Message) {
+        if let Some(guild_id) = msg.guild_id {
+            let alias_data = ctx.data.read().await;
+            let registry = alias_data
+                .get::<crate::AliasesKey>()
+                .expect("Failed to retrieve alias registry!")
+                .lock().await;
+            let commands = alias_data.get::<crate::CommandNamesKey>().expect("Failed to retrieve command names!");
+
+            if let Some(expanded) = registry.expand(guild_id, &self.prefix, &msg.content, commands) {
+                msg.content = expanded;
+            }
+        }
+
+        self.inner.dispatch(ctx, msg).await;
+    }
+}
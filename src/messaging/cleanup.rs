@@ -0,0 +1,121 @@
+use chrono::prelude::*;
+
+use serenity::{
+    model::{
+        channel::{
+            Message,
+            Reaction,
+        },
+        id::{
+            MessageId,
+            UserId,
+        },
+    },
+    prelude::*,
+};
+use std::{
+    collections::HashMap,
+    time::Duration,
+};
+
+pub type RepliesMap = HashMap<MessageId, TrackedReply>;
+
+#[derive(Debug)]
+pub struct TrackedReply {
+    pub invoker: UserId,
+    pub sent: DateTime<Utc>,
+}
+
+// Remember who a reply was sent to, so they can delete it by reacting to it later
+pub async fn track_reply(ctx: &Context, reply: &Message, invoker: UserId) {
+    let window = {
+        let config_data = ctx.data.read().await;
+        let cfg = config_data.get::<crate::ConfigKey>().expect("Failed to retrieve config!");
+        cfg.cleanup.delete_window_secs
+    };
+
+    let reply_data = ctx.data.read().await;
+    let mut replies = reply_data
+        .get::<crate::RepliesKey>()
+        .expect("Failed to retrieve replies map!")
+        .lock().await;
+
+    // Replies past the deletion window can't be deleted anymore, so there's no need to keep them around
+    let now = Utc::now();
+    replies.retain(|_, tracked| (now - tracked.sent).num_seconds() < window as i64);
+    replies.insert(reply.id, TrackedReply { invoker, sent: now });
+}
+
+// Delete a reply after the configured time to live, if there is one
+pub async fn schedule_cleanup(ctx: &Context, reply: Message) {
+    let ttl = {
+        let config_data = ctx.data.read().await;
+        let cfg = config_data.get::<crate::ConfigKey>().expect("Failed to retrieve config!");
+        cfg.cleanup.funsies_ttl_secs
+    };
+
+    if ttl == 0 { return; }
+
+    let http = ctx.http.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(ttl)).await;
+        if let Err(why) = reply.channel_id.delete_message(&*http, reply.id).await {
+            println!("Error cleaning up reply: {:?}", why);
+        }
+    });
+}
+
+pub async fn handle_delete_reaction(ctx: &Context, reaction: &Reaction) -> Result<(), serenity::Error> {
+    let (delete_emoji, window) = {
+        let config_data = ctx.data.read().await;
+        let cfg = config_data.get::<crate::ConfigKey>().expect("Failed to retrieve config!");
+        (cfg.cleanup.delete_emoji.clone(), cfg.cleanup.delete_window_secs)
+    };
+
+    if reaction.emoji.to_string() != delete_emoji { return Ok(()); }
+
+    let user = match reaction.user_id {
+        Some(user) => user,
+        None => return Ok(()),
+    };
+
+    let invoker = {
+        let reply_data = ctx.data.read().await;
+        let replies = reply_data
+            .get::<crate::RepliesKey>()
+            .expect("Failed to retrieve replies map!")
+            .lock().await;
+
+        match replies.get(&reaction.message_id) {
+            Some(tracked) if (Utc::now() - tracked.sent).num_seconds() < window as i64 => tracked.invoker,
+            _ => return Ok(()),
+        }
+    };
+
+    // The person who asked for the reply can always delete it, moderators can delete anyone's
+    let allowed = user == invoker || is_moderator(ctx, reaction, user).await?;
+    if !allowed { return Ok(()); }
+
+    reaction.channel_id.delete_message(ctx, reaction.message_id).await?;
+
+    let reply_data = ctx.data.read().await;
+    reply_data
+        .get::<crate::RepliesKey>()
+        .expect("Failed to retrieve replies map!")
+        .lock().await
+        .remove(&reaction.message_id);
+
+    Ok(())
+}
+
+async fn is_moderator(ctx: &Context, reaction: &Reaction, user: UserId) -> Result<bool, serenity::Error> {
+    let guild = match reaction.guild_id {
+        Some(guild) => guild,
+        None => return Ok(false),
+    };
+
+    let member = guild.member(ctx, user).await?;
+    let permissions = member.permissions(ctx).await?;
+
+    Ok(permissions.manage_messages())
+}
@@ -0,0 +1,153 @@
+use serenity::{
+    framework::standard::CommandResult,
+    model::channel::Message,
+    prelude::*,
+};
+
+use crate::{
+    dice::{
+        pool::MAX_DICE,
+        roll::Roll,
+    },
+    messaging::{
+        cleanup::track_reply,
+        MAX_MESSAGE_LENGTH,
+    },
+    roll_observers,
+};
+
+// Rolls every `[[...]]` in an ordinary message, like `I attack [[1d20+5]] and deal [[2d6+3]] damage`, and replies
+// with the same message with the results filled in
+pub async fn reply_inline_rolls(ctx: &Context, msg: &Message) -> CommandResult {
+    if msg.author.bot { return Ok(()); }
+
+    let pieces = split_inline_rolls(&msg.content);
+    if !pieces.iter().any(|piece| matches!(piece, Piece::Roll(_))) { return Ok(()); }
+
+    // Brackets that don't hold a roll are probably just brackets, like a wiki link, so they're left as they are
+    // instead of getting an error. If none of them hold a roll, the message isn't answered at all.
+    let rolls = pieces
+        .iter()
+        .filter_map(|piece| match piece {
+            Piece::Roll(expression) => Some(expression.parse::<Roll>().ok()),
+            Piece::Text(_) => None,
+        })
+        .collect::<Vec<Option<Roll>>>();
+    if rolls.iter().all(Option::is_none) { return Ok(()); }
+
+    let total_dice = rolls.iter().flatten().map(Roll::dice_count).sum::<u64>();
+    if total_dice > u64::from(MAX_DICE) {
+        let dice_error = format!("☢ That's too many dice! ☢\nI can only roll {} dice at once, not {}.", MAX_DICE, total_dice);
+        msg.channel_id.say(&ctx.http, dice_error).await?;
+        return Ok(());
+    }
+
+    let rolled = rolls.iter().flatten().cloned().collect::<Vec<Roll>>();
+    roll_observers::publish(ctx, &rolled).await;
+
+    // Same fallbacks as !roll: the full breakdown, then sparklines, then just the totals
+    let mut reply = fill_in(msg, &pieces, &rolls, |dice| format!("{} = **{}**", dice, dice.total()));
+    if reply.chars().count() > MAX_MESSAGE_LENGTH {
+        reply = fill_in(msg, &pieces, &rolls, |dice| format!("{} = **{}**", dice.compact_breakdown(), dice.total()));
+    }
+    if reply.chars().count() > MAX_MESSAGE_LENGTH {
+        reply = fill_in(msg, &pieces, &rolls, |dice| format!("**{}**", dice.total()));
+    }
+    // The reply repeats the whole message, so only the author gets pinged, not any @everyone or roles in it
+    let reply = msg.channel_id.send_message(&ctx.http, |m| {
+        m.content(reply);
+        m.allowed_mentions(|mentions| mentions.empty_parse().users(vec![msg.author.id]))
+    }).await?;
+    track_reply(ctx, &reply, msg.author.id).await;
+
+    Ok(())
+}
+
+enum Piece<'a> {
+    Text(&'a str),
+    Roll(&'a str),
+}
+
+fn split_inline_rolls(content: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find("[[") {
+        let end = match closing_brackets(&rest[start + 2..]) {
+            Some(end) => start + 2 + end,
+            None => break,
+        };
+
+        pieces.push(Piece::Text(&rest[..start]));
+        pieces.push(Piece::Roll(&rest[start + 2..end]));
+        rest = &rest[end + 2..];
+    }
+    pieces.push(Piece::Text(rest));
+
+    pieces
+}
+
+// Finds the `]]` that closes an inline roll, skipping over arrays like `2d[6,10]` inside it
+fn closing_brackets(expression: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut chars = expression.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '[' => depth += 1,
+            ']' if depth > 0 => depth -= 1,
+            ']' if matches!(chars.peek(), Some((_, ']'))) => return Some(i),
+            _ => {},
+        }
+    }
+
+    None
+}
+
+fn fill_in(msg: &Message, pieces: &[Piece], rolls: &[Option<Roll>], show_roll: impl Fn(&Roll) -> String) -> String {
+    let mut rolls = rolls.iter();
+    let text = pieces
+        .iter()
+        .map(|piece| match piece {
+            Piece::Text(text) => text.to_string(),
+            Piece::Roll(expression) => match rolls.next() {
+                Some(Some(roll)) => show_roll(roll),
+                _ => format!("[[{}]]", expression),
+            },
+        })
+        .collect::<String>();
+
+    format!("{} {}", msg.author, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rolls_in(content: &str) -> Vec<&str> {
+        split_inline_rolls(content)
+            .into_iter()
+            .filter_map(|piece| match piece {
+                Piece::Roll(expression) => Some(expression),
+                Piece::Text(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_every_bracketed_roll() {
+        assert_eq!(rolls_in("I attack [[1d20+5]] and deal [[2d6+3]] damage"), vec!["1d20+5", "2d6+3"]);
+        assert_eq!(rolls_in("[[2d[6,10]]] and [[unclosed"), vec!["2d[6,10]"]);
+        assert!(rolls_in("no rolls [here]").is_empty());
+    }
+
+    #[test]
+    fn only_some_brackets_need_to_be_rolls() {
+        let rolls = rolls_in("see [[Some Wiki Page]] then [[1d6]]")
+            .into_iter()
+            .map(|expression| expression.parse::<Roll>().is_ok())
+            .collect::<Vec<bool>>();
+
+        assert_eq!(rolls, vec![false, true]);
+    }
+}
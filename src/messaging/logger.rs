@@ -49,7 +49,7 @@ impl Logger {
     pub fn end_log(&self) -> io::Result<String> {
         let mut file = &self.log_file;
         writeln!(file, "---LOG END---")?;
-        let path = format!("{}", self.log_path);
+        let path = self.log_path.to_string();
         Ok(path)
     }
 }
\ No newline at end of file
@@ -1,7 +1,9 @@
 use serenity::{
     async_trait,
     model::{
+        channel::Reaction,
         gateway::Ready,
+        interactions::Interaction,
     },
     prelude::*,
 };
@@ -10,8 +12,31 @@ pub struct Handler;
 
 #[async_trait]
 impl EventHandler for Handler {
-    async fn ready(&self, _: Context, ready: Ready) {
+    async fn ready(&self, ctx: Context, ready: Ready) {
         println!("{} is connected!", ready.user.name);
+
+        let rolling = {
+            let config_data = ctx.data.read().await;
+            let cfg = config_data.get::<crate::ConfigKey>().expect("Failed to retrieve config!");
+            cfg.features.rolling
+        };
+        if rolling {
+            if let Err(why) = super::slash_commands::register(&ctx).await {
+                println!("Error registering slash commands: {:?}", why);
+            }
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        if let Err(why) = super::slash_commands::handle(&ctx, &interaction).await {
+            println!("Error responding to slash command: {:?}", why);
+        }
+    }
+
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        if let Err(why) = super::cleanup::handle_delete_reaction(&ctx, &reaction).await {
+            println!("Error deleting reply: {:?}", why);
+        }
     }
 }
 
@@ -1,2 +1,11 @@
+pub mod alias_framework;
 pub mod message_handler;
-pub mod logger;
\ No newline at end of file
+pub mod slash_commands;
+pub mod typos;
+pub mod logger;
+pub mod cleanup;
+pub mod inline_rolls;
+pub mod render;
+
+// Discord won't send a message longer than this
+pub const MAX_MESSAGE_LENGTH: usize = 2000;
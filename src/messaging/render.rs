@@ -0,0 +1,40 @@
+use serenity::model::user::User;
+
+use crate::dice::report::{
+    ReportField,
+    RollReport,
+    Severity,
+};
+
+// Writes a roll report as a chat reply. Big pools won't fit in the space they have, so this tries sparklines
+// instead of the dice, and failing that just the totals.
+pub fn render_report(author: &User, report: &RollReport, max_length: usize) -> String {
+    if report.severity == Severity::Error {
+        return format!("☢ I don't know how to roll that! ☢\n Error parsing dice: {}", report.annotations.join("\n"));
+    }
+
+    let mut roll = format_fields(author, report, |field| format!("{} = **{}**", field.breakdown, field.total));
+    if roll.chars().count() > max_length {
+        roll = format_fields(author, report, |field| format!("{} = **{}**", field.compact, field.total));
+    }
+    if roll.chars().count() > max_length {
+        roll = format_fields(author, report, |field| format!("**{}**", field.total));
+    }
+
+    roll
+}
+
+fn format_fields(author: &User, report: &RollReport, show_field: impl Fn(&ReportField) -> String) -> String {
+    if let [single] = report.fields.as_slice() {
+        return format!("{} You rolled {}", author, show_field(single));
+    }
+
+    let lines = report.fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| format!("`{}.` {}", i + 1, show_field(field)))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!("{} You rolled {} times:\n{}\nSum: **{}**", author, report.fields.len(), lines, report.value.unwrap_or(0))
+}
@@ -0,0 +1,179 @@
+use serenity::{
+    model::{
+        id::{
+            GuildId,
+            UserId,
+        },
+        interactions::{
+            ApplicationCommand,
+            ApplicationCommandInteractionDataOption,
+            ApplicationCommandOptionType,
+            Interaction,
+            InteractionApplicationCommandCallbackDataFlags,
+            InteractionResponseType,
+            InteractionType,
+        },
+    },
+    prelude::*,
+};
+
+use crate::{
+    commands::rolling::roll_report,
+    dice::report::Severity,
+    messaging::{
+        render::render_report,
+        MAX_MESSAGE_LENGTH,
+    },
+};
+
+const ROLL_NAME: &str = "roll";
+const ROLL_DESCRIPTION: &str = "Rolls dice, like 2d6+3, or one of your saved rolls";
+// Name, description, type and whether it's required
+const ROLL_OPTIONS: [(&str, &str, ApplicationCommandOptionType, bool); 3] = [
+    ("expression", "The dice to roll, like 4d6k3, or the name of a saved roll", ApplicationCommandOptionType::String, true),
+    ("comment", "What the roll is for", ApplicationCommandOptionType::String, false),
+    ("verbose", "Also show the dice a saved roll stands for", ApplicationCommandOptionType::Boolean, false),
+];
+
+// Slash commands are registered globally, so Discord can take up to an hour to show changes to them. Ready fires
+// again on every reconnect, so /roll is only sent to Discord when it's missing or has changed.
+pub async fn register(ctx: &Context) -> serenity::Result<()> {
+    let application_id = ctx.http.get_current_application_info().await?.id.0;
+
+    let existing = ctx.http.get_global_application_commands(application_id).await?;
+    if existing.iter().any(is_current_roll) { return Ok(()); }
+
+    Interaction::create_global_application_command(&ctx.http, application_id, |command| {
+        command.name(ROLL_NAME).description(ROLL_DESCRIPTION);
+        for (name, description, kind, required) in ROLL_OPTIONS.iter() {
+            command.create_interaction_option(|option| {
+                option
+                    .name(name)
+                    .description(description)
+                    .kind(*kind)
+                    .required(*required)
+            });
+        }
+        command
+    }).await?;
+
+    Ok(())
+}
+
+fn is_current_roll(command: &ApplicationCommand) -> bool {
+    command.name == ROLL_NAME
+        && command.description == ROLL_DESCRIPTION
+        && command.options.len() == ROLL_OPTIONS.len()
+        && command.options.iter().zip(ROLL_OPTIONS.iter()).all(|(option, (name, description, kind, required))| {
+            option.name == *name
+                && option.description == *description
+                && option.kind == *kind
+                && option.required == *required
+        })
+}
+
+pub async fn handle(ctx: &Context, interaction: &Interaction) -> serenity::Result<()> {
+    if interaction.kind != InteractionType::ApplicationCommand { return Ok(()); }
+
+    let data = match &interaction.data {
+        Some(data) => data,
+        None => return Ok(()),
+    };
+    if data.name != ROLL_NAME { return Ok(()); }
+
+    // /roll stays registered with Discord after rolling is turned off, until it's removed by hand
+    let rolling = {
+        let config_data = ctx.data.read().await;
+        let cfg = config_data.get::<crate::ConfigKey>().expect("Failed to retrieve config!");
+        cfg.features.rolling
+    };
+    if !rolling {
+        let disabled = "☢ Rolling is turned off! ☢\nThe dice commands are disabled on this bot, so /roll doesn't work right now.".to_string();
+        return respond(ctx, interaction, disabled, true).await;
+    }
+
+    // Serenity 0.10 only gives interactions from servers, a DM interaction has no member and is dropped before it
+    // gets here
+    let user = &interaction.member.user;
+    let guild_id = Some(interaction.guild_id);
+    let expression = string_option(&data.options, "expression").unwrap_or_default();
+    let verbose = bool_option(&data.options, "verbose").unwrap_or(false);
+
+    let report = roll_report(ctx, guild_id, user.id, &expression).await;
+    // Serenity 0.10 has no autocomplete to suggest saved rolls while typing, so they're listed when a roll fails instead
+    if report.severity == Severity::Error {
+        let mut error = render_report(user, &report, MAX_MESSAGE_LENGTH);
+        let saved = saved_roll_names(ctx, guild_id, user.id).await;
+        if !saved.is_empty() {
+            error.push_str(&format!("\nYour saved rolls here: {}", saved.join(", ")));
+        }
+        return respond(ctx, interaction, error.chars().take(MAX_MESSAGE_LENGTH).collect(), true).await;
+    }
+
+    let mut header = Vec::new();
+    if let Some(comment) = string_option(&data.options, "comment") {
+        header.push(format!("*{}*", comment));
+    }
+    // The annotations say which saved roll was used
+    if verbose {
+        header.extend(report.annotations.iter().cloned());
+    }
+
+    // The roll is shortened to leave room for the comment and expression, which only go if even that's too long
+    let header = header.join("\n");
+    let room = MAX_MESSAGE_LENGTH.saturating_sub(header.chars().count() + 1);
+    let mut reply = format!("{}\n{}", header, render_report(user, &report, room)).trim_start().to_string();
+    if reply.chars().count() > MAX_MESSAGE_LENGTH {
+        reply = render_report(user, &report, MAX_MESSAGE_LENGTH);
+    }
+
+    respond(ctx, interaction, reply, false).await
+}
+
+// Ephemeral replies are only shown to whoever used the command
+async fn respond(ctx: &Context, interaction: &Interaction, reply: String, ephemeral: bool) -> serenity::Result<()> {
+    let user_id = interaction.member.user.id;
+
+    interaction.create_interaction_response(&ctx.http, |response| {
+        response
+            .kind(InteractionResponseType::ChannelMessageWithSource)
+            // The comment is whatever the user typed, so it mustn't be able to ping @everyone or a role
+            .interaction_response_data(|message| {
+                message
+                    .content(reply)
+                    .allowed_mentions(|mentions| mentions.empty_parse().users(vec![user_id]));
+                if ephemeral { message.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL); }
+                message
+            })
+    }).await
+}
+
+async fn saved_roll_names(ctx: &Context, guild_id: Option<GuildId>, user: UserId) -> Vec<String> {
+    let macro_data = ctx.data.read().await;
+    let registry = macro_data
+        .get::<crate::MacrosKey>()
+        .expect("Failed to retrieve macro registry!")
+        .lock().await;
+
+    registry
+        .list(guild_id, user)
+        .map(|saved| saved.keys().map(|name| format!("`{}`", name)).collect())
+        .unwrap_or_default()
+}
+
+fn string_option(options: &[ApplicationCommandInteractionDataOption], name: &str) -> Option<String> {
+    options
+        .iter()
+        .find(|option| option.name == name)
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_str())
+        .map(str::to_string)
+}
+
+fn bool_option(options: &[ApplicationCommandInteractionDataOption], name: &str) -> Option<bool> {
+    options
+        .iter()
+        .find(|option| option.name == name)
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_bool())
+}
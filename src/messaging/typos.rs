@@ -0,0 +1,60 @@
+// How many single-character edits a mistyped command can be from the real one, same as the help command allows
+pub const MAX_TYPO_DISTANCE: usize = 2;
+
+// The command name closest to what was typed, if it's close enough to be a typo
+pub fn closest<'a>(typed: &str, names: &'a [String]) -> Option<&'a str> {
+    let typed = typed.to_lowercase();
+    if typed.is_empty() { return None; }
+    let max_edits = (typed.chars().count() / 2).max(1);
+
+    names
+        .iter()
+        .map(|name| (levenshtein(&typed, name), name))
+        // Short words are too easy to reach from anything, like `wod` from `ok`, so they get fewer edits
+        .filter(|(distance, _)| *distance <= MAX_TYPO_DISTANCE.min(max_edits))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name.as_str())
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levenshtein_counts_single_character_edits() {
+        assert_eq!(levenshtein("roll", "roll"), 0);
+        assert_eq!(levenshtein("rol", "roll"), 1);
+        assert_eq!(levenshtein("rpll", "roll"), 1);
+        assert_eq!(levenshtein("", "wod"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("dé", "de"), 1);
+    }
+
+    #[test]
+    fn closest_suggests_near_misses_only() {
+        let names = ["roll", "gmroll", "wod", "fate", "help"].iter().map(|name| name.to_string()).collect::<Vec<String>>();
+
+        assert_eq!(closest("rol", &names), Some("roll"));
+        assert_eq!(closest("ROLL", &names), Some("roll"));
+        assert_eq!(closest("gmrol", &names), Some("gmroll"));
+        assert_eq!(closest("ok", &names), None);
+        assert_eq!(closest("banana", &names), None);
+        assert_eq!(closest("", &names), None);
+    }
+}
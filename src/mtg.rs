@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use serenity::model::id::ChannelId;
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    io,
+};
+
+use crate::json_store;
+
+pub const DEFAULT_LIFE: i64 = 20;
+pub const COMMANDER_DAMAGE_LIMIT: u32 = 21;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LifeTotal {
+    pub player: u64,
+    pub life: i64,
+    // Commander damage taken, by the id of the player whose commander dealt it
+    #[serde(default)]
+    pub commander_damage: BTreeMap<u64, u32>,
+}
+
+impl LifeTotal {
+    pub fn is_out(&self) -> bool {
+        self.life <= 0 || self.commander_damage.values().any(|damage| *damage >= COMMANDER_DAMAGE_LIMIT)
+    }
+}
+
+// The state of a Magic game being played in one channel
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct MtgTable {
+    // User ids of whoever holds each title, if anyone
+    pub monarch: Option<u64>,
+    pub initiative: Option<u64>,
+    pub pod: Vec<LifeTotal>,
+}
+
+impl MtgTable {
+    pub fn start_pod(&mut self, players: &[u64], life: i64) {
+        self.pod = players
+            .iter()
+            .map(|player| LifeTotal { player: *player, life, commander_damage: BTreeMap::new() })
+            .collect();
+        self.monarch = None;
+        self.initiative = None;
+    }
+
+    pub fn life_total(&mut self, player: u64) -> Option<&mut LifeTotal> {
+        self.pod.iter_mut().find(|total| total.player == player)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct MtgTables {
+    #[serde(skip)]
+    path: String,
+    tables: HashMap<u64, MtgTable>,
+}
+
+impl MtgTables {
+    pub async fn load(path: &str) -> io::Result<MtgTables> {
+        let mut tables: MtgTables = json_store::load(path).await?;
+        tables.path = path.to_string();
+
+        Ok(tables)
+    }
+
+    pub fn get(&self, channel: ChannelId) -> MtgTable {
+        self.tables.get(&channel.0).cloned().unwrap_or_default()
+    }
+
+    pub async fn update(&mut self, channel: ChannelId, change: impl FnOnce(&mut MtgTable)) -> io::Result<()> {
+        change(self.tables.entry(channel.0).or_default());
+
+        self.persist().await
+    }
+
+    async fn persist(&self) -> io::Result<()> {
+        json_store::save(&self.path, self).await
+    }
+}
@@ -0,0 +1,91 @@
+use serenity::{
+    async_trait,
+    prelude::*,
+};
+use std::sync::Arc;
+
+use crate::dice::{
+    fairness::FaceTally,
+    roll::Roll,
+};
+
+// Anything that wants to hear about every roll, like stats or an audit log, so the commands don't each have to
+// call it themselves
+#[async_trait]
+pub trait RollObserver: Send + Sync {
+    async fn roll_completed(&self, roll: &Roll);
+}
+
+// Observers are subscribed once at startup, and are told about rolls in the order they subscribed
+#[derive(Default)]
+pub struct RollObservers {
+    observers: Vec<Arc<dyn RollObserver>>,
+}
+
+impl RollObservers {
+    pub fn subscribe(&mut self, observer: Arc<dyn RollObserver>) {
+        self.observers.push(observer);
+    }
+
+    pub async fn notify(&self, roll: &Roll) {
+        for observer in &self.observers {
+            observer.roll_completed(roll).await;
+        }
+    }
+}
+
+#[async_trait]
+impl RollObserver for Mutex<FaceTally> {
+    async fn roll_completed(&self, roll: &Roll) {
+        self.lock().await.record(roll);
+    }
+}
+
+// Tells every observer about the rolls a command just made
+pub async fn publish(ctx: &Context, rolls: &[Roll]) {
+    // The observers are cloned out so the data lock isn't held while they run
+    let observers = {
+        let observer_data = ctx.data.read().await;
+        Arc::clone(observer_data.get::<crate::RollObserversKey>().expect("Failed to retrieve roll observers!"))
+    };
+
+    for roll in rolls {
+        observers.notify(roll).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+
+    #[derive(Default)]
+    struct Counter(AtomicUsize);
+
+    #[async_trait]
+    impl RollObserver for Counter {
+        async fn roll_completed(&self, _roll: &Roll) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn every_observer_hears_every_roll() {
+        let counter = Arc::new(Counter::default());
+        let tally = Arc::new(Mutex::new(FaceTally::default()));
+        let mut observers = RollObservers::default();
+        observers.subscribe(counter.clone());
+        observers.subscribe(tally.clone());
+
+        let roll = "3d6".parse::<Roll>().unwrap();
+        for _ in 0..2 {
+            observers.notify(&roll).await;
+        }
+
+        assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+        assert_eq!(tally.lock().await.rolls(6), 6);
+    }
+}
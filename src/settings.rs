@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{
+    ChannelId,
+    GuildId,
+};
+use std::{
+    collections::{
+        BTreeSet,
+        HashMap,
+    },
+    io,
+};
+
+use crate::json_store;
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct FunsiesSettings {
+    pub disabled: bool,
+    // Empty means funsies work in every channel
+    pub channels: BTreeSet<u64>,
+}
+
+impl FunsiesSettings {
+    pub fn allowed_in(&self, channel: ChannelId) -> bool {
+        !self.disabled && (self.channels.is_empty() || self.channels.contains(&channel.0))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct GuildSettings {
+    pub funsies: FunsiesSettings,
+    // Members with this role get a copy of every !gmroll
+    pub gm_role: Option<u64>,
+    // Stops !usage from counting commands used in this guild
+    pub usage_opt_out: bool,
+}
+
+// Settings that each guild's admins can change for themselves
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SettingsRegistry {
+    #[serde(skip)]
+    path: String,
+    guilds: HashMap<u64, GuildSettings>,
+}
+
+impl SettingsRegistry {
+    pub async fn load(path: &str) -> io::Result<SettingsRegistry> {
+        let mut registry: SettingsRegistry = json_store::load(path).await?;
+        registry.path = path.to_string();
+
+        Ok(registry)
+    }
+
+    // Guilds that never changed anything get the defaults
+    pub fn get(&self, guild: GuildId) -> GuildSettings {
+        self.guilds.get(&guild.0).cloned().unwrap_or_default()
+    }
+
+    pub async fn update(&mut self, guild: GuildId, change: impl FnOnce(&mut GuildSettings)) -> io::Result<()> {
+        change(self.guilds.entry(guild.0).or_default());
+
+        self.persist().await
+    }
+
+    async fn persist(&self) -> io::Result<()> {
+        json_store::save(&self.path, self).await
+    }
+}
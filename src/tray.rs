@@ -0,0 +1,94 @@
+use serenity::model::id::{
+    ChannelId,
+    MessageId,
+    UserId,
+};
+use std::collections::{
+    HashMap,
+    VecDeque,
+};
+
+use crate::dice::systems::yze::YzeRoll;
+
+// How many !yze rolls each channel remembers
+pub const TRAY_SIZE: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct TrayEntry {
+    // My reply showing the roll, which players reply to when they want to push it
+    pub message: MessageId,
+    pub roller: UserId,
+    pub roll: YzeRoll,
+    // Whether this roll has been pushed already, since a roll can only be pushed once
+    pub pushed: bool,
+}
+
+// The recent !yze rolls in each channel, oldest first, so any of them can be pushed and not only the last one.
+// Only kept until the bot restarts.
+#[derive(Debug, Default)]
+pub struct YzeTray {
+    channels: HashMap<ChannelId, VecDeque<TrayEntry>>,
+}
+
+impl YzeTray {
+    pub fn record(&mut self, channel: ChannelId, message: MessageId, roller: UserId, roll: YzeRoll) {
+        let rolls = self.channels.entry(channel).or_default();
+        if rolls.len() == TRAY_SIZE {
+            rolls.pop_front();
+        }
+        rolls.push_back(TrayEntry { message, roller, roll, pushed: false });
+    }
+
+    // The roll that was replied to, or without a reply the roller's latest roll in the channel
+    pub fn find_mut(&mut self, channel: ChannelId, roller: UserId, replied_to: Option<MessageId>) -> Option<&mut TrayEntry> {
+        let rolls = self.channels.get_mut(&channel)?;
+
+        match replied_to {
+            Some(message) => rolls.iter_mut().find(|entry| entry.message == message),
+            None => rolls.iter_mut().rev().find(|entry| entry.roller == roller),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANNEL: ChannelId = ChannelId(1);
+
+    fn tray(rollers: &[u64]) -> YzeTray {
+        let mut tray = YzeTray::default();
+        for (i, roller) in rollers.iter().enumerate() {
+            tray.record(CHANNEL, MessageId(i as u64), UserId(*roller), YzeRoll::new(2, 0, 0));
+        }
+
+        tray
+    }
+
+    #[test]
+    fn finds_the_latest_roll_by_the_roller() {
+        let mut tray = tray(&[7, 8, 7, 8]);
+
+        assert_eq!(tray.find_mut(CHANNEL, UserId(7), None).map(|entry| entry.message), Some(MessageId(2)));
+        assert!(tray.find_mut(CHANNEL, UserId(9), None).is_none());
+        assert!(tray.find_mut(ChannelId(2), UserId(7), None).is_none());
+    }
+
+    #[test]
+    fn finds_the_roll_that_was_replied_to() {
+        let mut tray = tray(&[7, 8, 7]);
+
+        let entry = tray.find_mut(CHANNEL, UserId(7), Some(MessageId(1))).unwrap();
+        assert_eq!(entry.roller, UserId(8));
+        assert!(tray.find_mut(CHANNEL, UserId(7), Some(MessageId(99))).is_none());
+    }
+
+    #[test]
+    fn forgets_the_oldest_rolls() {
+        let mut tray = tray(&[7; TRAY_SIZE + 3]);
+
+        assert!(tray.find_mut(CHANNEL, UserId(7), Some(MessageId(2))).is_none());
+        assert!(tray.find_mut(CHANNEL, UserId(7), Some(MessageId(3))).is_some());
+        assert_eq!(tray.channels[&CHANNEL].len(), TRAY_SIZE);
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use serenity::model::id::GuildId;
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    io,
+};
+
+use crate::json_store;
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct CommandUsage {
+    pub uses: u64,
+    // Times the command returned an error to the framework. Mistakes I answer with a reply don't count here.
+    #[serde(default)]
+    pub errors: u64,
+}
+
+// How often each command is used per guild, DMs under guild 0. Only command names and counts are kept, never
+// what was in the message.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct UsageRegistry {
+    #[serde(skip)]
+    path: String,
+    // Counts change on every command, so they're only written out every so often by flush
+    #[serde(skip)]
+    dirty: bool,
+    usage: HashMap<u64, BTreeMap<String, CommandUsage>>,
+}
+
+impl UsageRegistry {
+    pub async fn load(path: &str) -> io::Result<UsageRegistry> {
+        let mut registry: UsageRegistry = json_store::load(path).await?;
+        registry.path = path.to_string();
+
+        Ok(registry)
+    }
+
+    pub fn get(&self, guild: Option<GuildId>) -> Option<&BTreeMap<String, CommandUsage>> {
+        self.usage.get(&guild_key(guild))
+    }
+
+    pub fn record(&mut self, guild: Option<GuildId>, command: &str, failed: bool) {
+        let usage = self.usage
+            .entry(guild_key(guild))
+            .or_default()
+            .entry(command.to_string())
+            .or_default();
+        usage.uses += 1;
+        if failed { usage.errors += 1; }
+        self.dirty = true;
+    }
+
+    // Writes out the counts recorded since the last flush, if there are any
+    pub async fn flush(&mut self) -> io::Result<()> {
+        if !self.dirty { return Ok(()); }
+
+        self.persist().await?;
+        self.dirty = false;
+
+        Ok(())
+    }
+
+    // Opting out is saved straight away rather than waiting for the next flush
+    pub async fn clear(&mut self, guild: GuildId) -> io::Result<()> {
+        if self.usage.remove(&guild.0).is_some() {
+            self.persist().await?;
+            self.dirty = false;
+        }
+
+        Ok(())
+    }
+
+    async fn persist(&self) -> io::Result<()> {
+        json_store::save(&self.path, self).await
+    }
+}
+
+fn guild_key(guild: Option<GuildId>) -> u64 {
+    guild.map(|guild| guild.0).unwrap_or(0)
+}